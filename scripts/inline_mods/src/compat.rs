@@ -0,0 +1,393 @@
+use proc_macro2::{Delimiter, Spacing, Span, TokenStream, TokenTree};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, GenericParam};
+
+use crate::utils::RustPathBuf;
+
+/// Std methods and the minor version of Rust 1.x that stabilized them
+const STD_METHODS: [(&str, u32); 33] = [
+    ("unsigned_abs", 51),
+    ("split_once", 52),
+    ("rsplit_once", 52),
+    ("map_while", 57),
+    ("abs_diff", 60),
+    ("retain_mut", 61),
+    ("then_some", 62),
+    ("total_cmp", 62),
+    ("checked_add_signed", 66),
+    ("wrapping_add_signed", 66),
+    ("saturating_add_signed", 66),
+    ("pop_first", 66),
+    ("pop_last", 66),
+    ("first_key_value", 66),
+    ("last_key_value", 66),
+    ("ilog", 67),
+    ("ilog2", 67),
+    ("ilog10", 67),
+    ("checked_ilog2", 67),
+    ("is_some_and", 70),
+    ("is_ok_and", 70),
+    ("is_err_and", 70),
+    ("div_ceil", 73),
+    ("next_multiple_of", 73),
+    ("chunk_by", 77),
+    ("take_if", 80),
+    ("trim_ascii", 80),
+    ("is_sorted", 82),
+    ("is_sorted_by_key", 82),
+    ("is_none_or", 82),
+    ("isqrt", 84),
+    ("midpoint", 85),
+    ("pop_if", 86),
+];
+
+/// Std paths (matched by suffix) and the minor version of Rust 1.x that stabilized them
+const STD_PATHS: [(&str, u32); 16] = [
+    ("u32::BITS", 53),
+    ("u64::BITS", 53),
+    ("usize::BITS", 53),
+    ("i32::BITS", 53),
+    ("i64::BITS", 53),
+    ("HashMap::from", 56),
+    ("HashSet::from", 56),
+    ("BTreeMap::from", 56),
+    ("BTreeSet::from", 56),
+    ("iter::zip", 59),
+    ("array::from_fn", 63),
+    ("thread::scope", 63),
+    ("OnceLock", 70),
+    ("OnceCell", 70),
+    ("LazyLock", 80),
+    ("iter::repeat_n", 82),
+];
+
+const FORMAT_MACROS: [&str; 14] = [
+    "format",
+    "format_args",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "write",
+    "writeln",
+    "panic",
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "unreachable",
+    "todo",
+];
+
+pub struct Hit {
+    pub mod_path: RustPathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub feature: String,
+    pub since: u32,
+}
+
+/// Parses `1.x` or `1.x.y` into `x`
+pub fn parse_rust_version(version: &str) -> u32 {
+    let mut parts = version.split('.');
+    match (parts.next(), parts.next().map(|minor| minor.parse())) {
+        (Some("1"), Some(Ok(minor))) => minor,
+        _ => panic!("unsupported Rust version {version:?}, expected 1.x"),
+    }
+}
+
+/// Syntax and std APIs newer than Rust `1.{minor}`, std APIs are matched by name only.
+/// Macro arguments and `macro_rules!` bodies are checked on the token level.
+pub fn find_incompatible(file: &syn::File, minor: u32) -> Vec<Hit> {
+    let mut visitor = CompatVisitor {
+        minor,
+        mod_path: Vec::new(),
+        hits: Vec::new(),
+    };
+    visitor.visit_file(file);
+    visitor.hits
+}
+
+struct CompatVisitor {
+    minor: u32,
+    mod_path: RustPathBuf,
+    hits: Vec<Hit>,
+}
+
+impl CompatVisitor {
+    fn record(&mut self, span: Span, feature: String, since: u32) {
+        if since > self.minor {
+            self.hits.push(Hit {
+                mod_path: self.mod_path.clone(),
+                line: span.start().line,
+                column: span.start().column + 1,
+                feature,
+                since,
+            });
+        }
+    }
+
+    fn check_method(&mut self, name: &str, span: Span) {
+        if let Some(&(_, since)) = STD_METHODS.iter().find(|(method, _)| *method == name) {
+            self.record(span, format!("method `{name}`"), since);
+        }
+    }
+
+    fn check_path(&mut self, segments: &[String], span: Span) {
+        let joined = segments.join("::");
+        for (path, since) in STD_PATHS {
+            if joined == path || joined.ends_with(&format!("::{path}")) {
+                self.record(span, format!("`{path}`"), since);
+            }
+        }
+    }
+
+    fn check_format_args(&mut self, tokens: &[TokenTree]) {
+        let named_args: Vec<_> = tokens
+            .windows(3)
+            .filter_map(|window| match window {
+                [TokenTree::Punct(comma), TokenTree::Ident(name), TokenTree::Punct(eq)]
+                    if comma.as_char() == ','
+                        && eq.as_char() == '='
+                        && eq.spacing() == Spacing::Alone =>
+                {
+                    Some(name.to_string())
+                }
+                _ => None,
+            })
+            .collect();
+        let format_str = tokens.iter().find_map(|token| match token {
+            TokenTree::Literal(literal) if literal.to_string().ends_with('"') => Some(literal),
+            _ => None,
+        });
+        if let Some(literal) = format_str {
+            let captured = captured_args(&literal.to_string())
+                .into_iter()
+                .find(|name| !named_args.contains(name));
+            if let Some(name) = captured {
+                self.record(
+                    literal.span(),
+                    format!("captured format argument `{{{name}}}`"),
+                    58,
+                );
+            }
+        }
+    }
+
+    fn scan_tokens(&mut self, tokens: TokenStream) {
+        let tokens: Vec<_> = tokens.into_iter().collect();
+        let mut i = 0;
+        while i < tokens.len() {
+            match &tokens[i..] {
+                [TokenTree::Punct(dot), TokenTree::Ident(method), TokenTree::Group(group), ..]
+                    if dot.as_char() == '.' && group.delimiter() == Delimiter::Parenthesis =>
+                {
+                    self.check_method(&method.to_string(), method.span());
+                    i += 1;
+                }
+                [TokenTree::Ident(ident), rest @ ..] => {
+                    let mut segments = vec![ident.to_string()];
+                    let mut j = 0;
+                    while let [TokenTree::Punct(p1), TokenTree::Punct(p2), TokenTree::Ident(next), ..] =
+                        &rest[j..]
+                    {
+                        if p1.as_char() != ':' || p2.as_char() != ':' {
+                            break;
+                        }
+                        segments.push(next.to_string());
+                        j += 3;
+                    }
+                    self.check_path(&segments, ident.span());
+                    i += j + 1;
+                }
+                [TokenTree::Group(group), ..] => {
+                    self.scan_tokens(group.stream());
+                    i += 1;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+}
+
+/// Names of the `{name}` placeholders of a format string literal
+fn captured_args(literal: &str) -> Vec<String> {
+    let mut res = Vec::new();
+    let mut chars = literal.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            continue;
+        }
+        let name: String = chars
+            .by_ref()
+            .take_while(|&c| c != '}' && c != ':')
+            .collect();
+        if name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            res.push(name);
+        }
+    }
+    res
+}
+
+impl<'ast> Visit<'ast> for CompatVisitor {
+    fn visit_item_mod(&mut self, item_mod: &'ast syn::ItemMod) {
+        self.mod_path.push(item_mod.ident.to_string());
+        visit::visit_item_mod(self, item_mod);
+        self.mod_path.pop();
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if local
+            .init
+            .as_ref()
+            .is_some_and(|init| init.diverge.is_some())
+        {
+            self.record(local.let_token.span, String::from("`let ... else`"), 65);
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_generic_param(&mut self, param: &'ast GenericParam) {
+        if let GenericParam::Const(param) = param {
+            self.record(param.span(), String::from("const generics"), 51);
+        }
+        visit::visit_generic_param(self, param);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.check_method(&call.method.to_string(), call.method.span());
+        if call.method == "into_iter" && matches!(*call.receiver, Expr::Array(_)) {
+            self.record(call.span(), String::from("by-value array iteration"), 53);
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast syn::ExprForLoop) {
+        if matches!(*for_loop.expr, Expr::Array(_)) {
+            self.record(
+                for_loop.expr.span(),
+                String::from("by-value array iteration"),
+                53,
+            );
+        }
+        visit::visit_expr_for_loop(self, for_loop);
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign) {
+        if matches!(
+            *assign.left,
+            Expr::Tuple(_) | Expr::Array(_) | Expr::Struct(_) | Expr::Call(_)
+        ) {
+            self.record(
+                assign.left.span(),
+                String::from("destructuring assignment"),
+                59,
+            );
+        }
+        visit::visit_expr_assign(self, assign);
+    }
+
+    fn visit_expr_block(&mut self, block: &'ast syn::ExprBlock) {
+        if let Some(label) = &block.label {
+            self.record(label.span(), String::from("labeled block"), 65);
+        }
+        visit::visit_expr_block(self, block);
+    }
+
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        let segments: Vec<_> = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        self.check_path(&segments, path.span());
+        visit::visit_path(self, path);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let tokens: Vec<_> = mac.tokens.clone().into_iter().collect();
+        if let Some(name) = mac.path.segments.last() {
+            if FORMAT_MACROS.contains(&name.ident.to_string().as_str()) {
+                self.check_format_args(&tokens);
+            }
+        }
+        self.scan_tokens(mac.tokens.clone());
+        visit::visit_macro(self, mac);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_incompatible, parse_rust_version};
+
+    fn features(src: &str, version: &str) -> Vec<String> {
+        let file = syn::parse_file(src).unwrap();
+        find_incompatible(&file, parse_rust_version(version))
+            .into_iter()
+            .map(|hit| {
+                format!(
+                    "{}:{}:{} {} 1.{}",
+                    hit.mod_path.join("::"),
+                    hit.line,
+                    hit.column,
+                    hit.feature,
+                    hit.since
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn compat_syntax_and_std() {
+        let src = "mod math {
+    pub struct Modulo<const MOD: u64>(u64);
+    fn f(a: &[usize], v: Option<usize>) -> usize {
+        let Some(start) = v else {
+            return a.len().abs_diff(1);
+        };
+        (start > 0).then_some(1);
+        for x in [1, 2] {}
+        std::array::from_fn::<usize, 3, _>(|i| i);
+        let n = 5;
+        println!(\"{n} {} {m}\", n, m = 1);
+        start
+    }
+}";
+        assert_eq!(
+            features(src, "1.49.0"),
+            vec![
+                "math:2:23 const generics 1.51",
+                "math:4:9 `let ... else` 1.65",
+                "math:5:28 method `abs_diff` 1.60",
+                "math:7:21 method `then_some` 1.62",
+                "math:8:18 by-value array iteration 1.53",
+                "math:9:9 `array::from_fn` 1.63",
+                "math:11:18 captured format argument `{n}` 1.58",
+            ]
+        );
+        assert_eq!(
+            features(src, "1.62"),
+            vec![
+                "math:4:9 `let ... else` 1.65",
+                "math:9:9 `array::from_fn` 1.63",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn compat_macro_bodies() {
+        let src = "macro_rules! m {
+    ($x:expr) => {
+        $x.is_some_and(|v| v > 0) && $crate::utils::misc::helper() && std::iter::zip(a, b).count() > 0
+    };
+}";
+        assert_eq!(
+            features(src, "1.58"),
+            vec![":3:12 method `is_some_and` 1.70", ":3:71 `iter::zip` 1.59",]
+        );
+    }
+}
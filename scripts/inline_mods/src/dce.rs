@@ -0,0 +1,465 @@
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{Item, TraitItem, UseTree};
+
+/// Item-level dead code elimination over an inlined crate.
+///
+/// Items are matched by name only, so every reference to a name keeps all the items with that name.
+/// Crate root items (except mods) are the roots, everything reachable from them is kept:
+/// - named items (fns, structs, traits, macros, ...) are reached by their name;
+/// - traits are also reached by the names of their methods;
+/// - impls are kept once all the local types and traits they mention in the header are reached;
+/// - item-level macro calls can define anything, so they are kept together with their mod;
+/// - `use` leaves pointing to local items are kept only if the imported name is reached.
+pub fn eliminate_dead_code(file: &mut syn::File) -> usize {
+    let mut graph = Graph::default();
+    graph.add_items(&file.items, None, String::new());
+    graph.resolve_local_types();
+    graph.traverse();
+    let mut cursor = Cursor::default();
+    let removed = graph.prune_items(&mut file.items, &mut cursor);
+    assert_eq!(cursor.node, graph.nodes.len());
+    assert_eq!(cursor.module + 1, graph.modules.len());
+    removed
+}
+
+/// Position in the nodes and modules while walking the items in the same order as `add_items`
+#[derive(Default)]
+struct Cursor {
+    node: usize,
+    module: usize,
+}
+
+enum NodeKind {
+    Named,
+    Impl { header: Vec<String> },
+    MacroCall,
+    UseLeaf { local: bool, glob: bool },
+    Always,
+}
+
+struct Node {
+    kind: NodeKind,
+    names: Vec<String>,
+    deps: Vec<String>,
+    module: usize,
+}
+
+struct Module {
+    parent: Option<usize>,
+    active: bool,
+}
+
+#[derive(Default)]
+struct Graph {
+    nodes: Vec<Node>,
+    modules: Vec<Module>,
+    by_name: HashMap<String, Vec<usize>>,
+    modules_by_name: HashMap<String, Vec<usize>>,
+    local_types: HashSet<String>,
+    reached: Vec<bool>,
+    reached_names: HashSet<String>,
+}
+
+impl Graph {
+    fn add_items(&mut self, items: &[Item], parent: Option<usize>, name: String) {
+        let module = self.modules.len();
+        self.modules.push(Module {
+            parent,
+            active: false,
+        });
+        self.modules_by_name.entry(name).or_default().push(module);
+        for item in items {
+            match item {
+                Item::Mod(item_mod) => {
+                    if let Some((_, content)) = &item_mod.content {
+                        self.add_items(content, Some(module), item_mod.ident.to_string());
+                    } else {
+                        self.add_node(NodeKind::Always, vec![], item, module);
+                    }
+                }
+                Item::Use(item_use) => {
+                    let local = !matches!(
+                        use_tree_root(&item_use.tree).as_str(),
+                        "std" | "core" | "alloc"
+                    );
+                    self.add_use_leaves(&item_use.tree, local, module, &mut vec![]);
+                }
+                Item::Impl(item_impl) => {
+                    let mut header = Vec::new();
+                    collect_idents(item_impl.self_ty.to_token_stream(), &mut header);
+                    if let Some((_, trait_path, _)) = &item_impl.trait_ {
+                        collect_idents(trait_path.to_token_stream(), &mut header);
+                    }
+                    self.add_node(NodeKind::Impl { header }, vec![], item, module);
+                }
+                Item::Trait(item_trait) => {
+                    self.local_types.insert(item_trait.ident.to_string());
+                    let mut names = vec![item_trait.ident.to_string()];
+                    for trait_item in item_trait.items.iter() {
+                        match trait_item {
+                            TraitItem::Fn(method) => names.push(method.sig.ident.to_string()),
+                            TraitItem::Const(item_const) => {
+                                names.push(item_const.ident.to_string())
+                            }
+                            TraitItem::Type(item_type) => names.push(item_type.ident.to_string()),
+                            _ => {}
+                        }
+                    }
+                    self.add_node(NodeKind::Named, names, item, module);
+                }
+                Item::Macro(item_macro) => match &item_macro.ident {
+                    Some(ident) => {
+                        self.add_node(NodeKind::Named, vec![ident.to_string()], item, module)
+                    }
+                    None => {
+                        let mut names = Vec::new();
+                        collect_idents(item_macro.mac.tokens.clone(), &mut names);
+                        self.add_node(NodeKind::MacroCall, names, item, module);
+                    }
+                },
+                Item::Struct(syn::ItemStruct { ident, .. })
+                | Item::Enum(syn::ItemEnum { ident, .. })
+                | Item::Union(syn::ItemUnion { ident, .. })
+                | Item::Type(syn::ItemType { ident, .. })
+                | Item::TraitAlias(syn::ItemTraitAlias { ident, .. }) => {
+                    self.local_types.insert(ident.to_string());
+                    self.add_node(NodeKind::Named, vec![ident.to_string()], item, module);
+                }
+                Item::Fn(syn::ItemFn { sig, .. }) => {
+                    self.add_node(NodeKind::Named, vec![sig.ident.to_string()], item, module);
+                }
+                Item::Const(syn::ItemConst { ident, .. })
+                | Item::Static(syn::ItemStatic { ident, .. }) => {
+                    self.add_node(NodeKind::Named, vec![ident.to_string()], item, module);
+                }
+                _ => self.add_node(NodeKind::Always, vec![], item, module),
+            }
+        }
+    }
+
+    /// `prefix` holds the path segments leading to the tree, they are the dependencies of every leaf
+    fn add_use_leaves(
+        &mut self,
+        tree: &UseTree,
+        local: bool,
+        module: usize,
+        prefix: &mut Vec<String>,
+    ) {
+        let mut deps = prefix.clone();
+        let (glob, names) = match tree {
+            UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                self.add_use_leaves(&path.tree, local, module, prefix);
+                prefix.pop();
+                return;
+            }
+            UseTree::Group(group) => {
+                for item in group.items.iter() {
+                    self.add_use_leaves(item, local, module, prefix);
+                }
+                return;
+            }
+            UseTree::Name(use_name) => (false, vec![use_name.ident.to_string()]),
+            UseTree::Rename(use_rename) => {
+                deps.push(use_rename.ident.to_string());
+                (false, vec![use_rename.rename.to_string()])
+            }
+            UseTree::Glob(_) => (true, vec![]),
+        };
+        self.push_node(NodeKind::UseLeaf { local, glob }, names, deps, module);
+    }
+
+    fn add_node(&mut self, kind: NodeKind, names: Vec<String>, item: &Item, module: usize) {
+        let mut deps = Vec::new();
+        collect_idents(item.to_token_stream(), &mut deps);
+        self.push_node(kind, names, deps, module);
+    }
+
+    fn push_node(&mut self, kind: NodeKind, names: Vec<String>, deps: Vec<String>, module: usize) {
+        let node_i = self.nodes.len();
+        for name in names.iter() {
+            self.by_name
+                .entry(name.to_owned())
+                .or_default()
+                .push(node_i);
+        }
+        self.nodes.push(Node {
+            kind,
+            names,
+            deps,
+            module,
+        });
+    }
+
+    fn resolve_local_types(&mut self) {
+        for node in self.nodes.iter_mut() {
+            if let NodeKind::Impl { header } = &mut node.kind {
+                header.retain(|name| self.local_types.contains(name));
+            }
+        }
+    }
+
+    fn traverse(&mut self) {
+        self.reached = vec![false; self.nodes.len()];
+        let roots: Vec<_> = (0..self.nodes.len())
+            .filter(|&node_i| self.nodes[node_i].module == 0)
+            .collect();
+        for node_i in roots {
+            self.reach(node_i);
+        }
+        loop {
+            let mut changed = false;
+            for node_i in 0..self.nodes.len() {
+                if self.reached[node_i] {
+                    continue;
+                }
+                let node = &self.nodes[node_i];
+                let module_active = self.modules[node.module].active;
+                let ready = match &node.kind {
+                    NodeKind::Named => false,
+                    NodeKind::Impl { header } => {
+                        header.iter().all(|name| self.reached_names.contains(name))
+                    }
+                    NodeKind::UseLeaf { local, glob } => module_active && (!local || *glob),
+                    NodeKind::MacroCall | NodeKind::Always => module_active,
+                };
+                if ready {
+                    self.reach(node_i);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    fn reach(&mut self, node_i: usize) {
+        let mut stack = vec![node_i];
+        while let Some(node_i) = stack.pop() {
+            if self.reached[node_i] {
+                continue;
+            }
+            self.reached[node_i] = true;
+            self.activate(self.nodes[node_i].module);
+            let node = &self.nodes[node_i];
+            let mut new_names = Vec::new();
+            for name in node.names.iter().chain(node.deps.iter()) {
+                if self.reached_names.insert(name.to_owned()) {
+                    new_names.push(name.to_owned());
+                }
+            }
+            for name in new_names {
+                if let Some(node_ids) = self.by_name.get(&name) {
+                    stack.extend(node_ids.iter().filter(|&&i| !self.reached[i]));
+                }
+                for module_i in self.modules_by_name.get(&name).cloned().unwrap_or_default() {
+                    self.activate(module_i);
+                }
+            }
+        }
+    }
+
+    /// Active mods are kept in the output, together with all their parents
+    fn activate(&mut self, module_i: usize) {
+        let mut module = Some(module_i);
+        while let Some(module_i) = module {
+            if self.modules[module_i].active {
+                break;
+            }
+            self.modules[module_i].active = true;
+            module = self.modules[module_i].parent;
+        }
+    }
+
+    fn prune_items(&self, items: &mut Vec<Item>, cursor: &mut Cursor) -> usize {
+        let mut removed = 0;
+        items.retain_mut(|item| {
+            let keep = match item {
+                Item::Mod(syn::ItemMod {
+                    content: Some((_, content)),
+                    ..
+                }) => {
+                    cursor.module += 1;
+                    let module_i = cursor.module;
+                    removed += self.prune_items(content, cursor);
+                    self.modules[module_i].active
+                }
+                Item::Use(item_use) => self.prune_use_tree(&mut item_use.tree, cursor),
+                _ => self.next_reached(cursor),
+            };
+            if !keep {
+                removed += 1;
+            }
+            keep
+        });
+        removed
+    }
+
+    fn next_reached(&self, cursor: &mut Cursor) -> bool {
+        cursor.node += 1;
+        self.reached[cursor.node - 1]
+    }
+
+    fn prune_use_tree(&self, tree: &mut UseTree, cursor: &mut Cursor) -> bool {
+        match tree {
+            UseTree::Path(path) => self.prune_use_tree(&mut path.tree, cursor),
+            UseTree::Group(group) => {
+                let items = std::mem::take(&mut group.items);
+                for mut item in items.into_iter() {
+                    if self.prune_use_tree(&mut item, cursor) {
+                        group.items.push(item);
+                    }
+                }
+                !group.items.is_empty()
+            }
+            UseTree::Name(_) | UseTree::Rename(_) | UseTree::Glob(_) => self.next_reached(cursor),
+        }
+    }
+}
+
+fn use_tree_root(tree: &UseTree) -> String {
+    match tree {
+        UseTree::Path(path) => path.ident.to_string(),
+        UseTree::Name(use_name) => use_name.ident.to_string(),
+        UseTree::Rename(use_rename) => use_rename.ident.to_string(),
+        UseTree::Glob(_) | UseTree::Group(_) => String::new(),
+    }
+}
+
+fn collect_idents(tokens: TokenStream, out: &mut Vec<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                let name = ident.to_string();
+                out.push(name.strip_prefix("r#").unwrap_or(&name).to_owned());
+            }
+            TokenTree::Group(group) => collect_idents(group.stream(), out),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::eliminate_dead_code;
+
+    fn run(src: &str) -> (usize, String) {
+        let mut file = syn::parse_file(src).unwrap();
+        let removed = eliminate_dead_code(&mut file);
+        (removed, prettyplease::unparse(&file))
+    }
+
+    #[test]
+    pub fn dce_keeps_reachable_items() {
+        let src = "
+use crate::ds::dsu::Dsu;
+fn main() {
+    let mut dsu = Dsu::new(3);
+    dsu.join(0, 1);
+}
+mod ds {
+    pub mod dsu {
+        use std::fmt::Debug;
+        pub struct Dsu(Vec<usize>);
+        impl Dsu {
+            pub fn new(n: usize) -> Self {
+                Self(helper(n))
+            }
+        }
+        impl Debug for Dsu {}
+        fn helper(n: usize) -> Vec<usize> {
+            (0..n).collect()
+        }
+        pub struct Unused;
+        impl Unused {}
+        fn unused_fn() {}
+    }
+    pub mod seg_tree {
+        pub struct SegTree;
+    }
+}";
+        let expected = "use crate::ds::dsu::Dsu;
+fn main() {
+    let mut dsu = Dsu::new(3);
+    dsu.join(0, 1);
+}
+mod ds {
+    pub mod dsu {
+        use std::fmt::Debug;
+        pub struct Dsu(Vec<usize>);
+        impl Dsu {
+            pub fn new(n: usize) -> Self {
+                Self(helper(n))
+            }
+        }
+        impl Debug for Dsu {}
+        fn helper(n: usize) -> Vec<usize> {
+            (0..n).collect()
+        }
+    }
+}
+";
+        assert_eq!(run(src), (5, expected.to_owned()));
+    }
+
+    #[test]
+    pub fn dce_traits_and_macros() {
+        let src = "
+fn main() {
+    let v = 5.twice();
+    make!(Val);
+}
+mod ext {
+    pub trait TwiceExt {
+        fn twice(&self) -> Self;
+    }
+    impl TwiceExt for i32 {
+        fn twice(&self) -> Self {
+            self * 2
+        }
+    }
+    pub trait OtherExt {}
+    impl OtherExt for i32 {}
+}
+mod macros {
+    #[macro_export]
+    macro_rules! make {
+        ($name:ident) => {
+            struct $name;
+        };
+    }
+    #[macro_export]
+    macro_rules! unused {
+        () => {};
+    }
+}";
+        let expected = "fn main() {
+    let v = 5.twice();
+    make!(Val);
+}
+mod ext {
+    pub trait TwiceExt {
+        fn twice(&self) -> Self;
+    }
+    impl TwiceExt for i32 {
+        fn twice(&self) -> Self {
+            self * 2
+        }
+    }
+}
+mod macros {
+    #[macro_export]
+    macro_rules! make {
+        ($name:ident) => {
+            struct $name;
+        };
+    }
+}
+";
+        assert_eq!(run(src), (3, expected.to_owned()));
+    }
+}
@@ -1,30 +1,131 @@
-use std::collections::HashSet;
-use std::fs::read_to_string;
+use std::collections::{BTreeSet, HashSet};
+use std::fs::{read_dir, read_to_string};
 use std::path::Path;
 
-use crate::use_parser::parse_use;
-use crate::utils::{resolve_mod_path, RustPath, RustPathBuf};
+use syn::Item;
 
-pub fn record_used_mods(src_path: &Path) -> HashSet<RustPathBuf> {
-    let mut used_mods = HashSet::new();
-    process_file(src_path, &[], &mut used_mods);
-    used_mods
+use crate::use_parser::parse_use_file;
+use crate::utils::{
+    is_cfg_test, join_rust_paths, mod_file_path, parse_file_at, resolve_mod_path, RustPath,
+    RustPathBuf,
+};
+
+/// Used mods together with the `use` paths that caused them
+#[derive(Default)]
+pub struct ModGraph {
+    pub mods: HashSet<RustPathBuf>,
+    pub edges: BTreeSet<ModEdge>,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct ModEdge {
+    pub from: RustPathBuf,
+    pub to: RustPathBuf,
+    pub path: RustPathBuf,
+}
+
+pub fn record_used_mods(src_path: &Path) -> ModGraph {
+    let mut graph = ModGraph::default();
+    process_file(src_path, &[], &mut graph);
+    graph
 }
 
-fn process_file(src_path: &Path, cur_mod: &RustPath, used_mods: &mut HashSet<RustPathBuf>) {
-    if used_mods.contains(cur_mod) {
-        return;
+/// Same as `record_used_mods`, but starts from the given source of `cur_mod`
+/// instead of the whole crate, `cur_mod` itself is not recorded
+pub fn record_mods_used_by(src_path: &Path, source_code: &str, cur_mod: &RustPath) -> ModGraph {
+    let mut graph = ModGraph::default();
+    process_source(src_path, source_code, cur_mod, &mut graph);
+    graph
+}
+
+/// Library mods (everything outside of `plat`) used neither by `graph`
+/// nor by any of the solutions placed in `archive_path` as `solution_mod`
+pub fn record_unused_mods(
+    src_path: &Path,
+    graph: &ModGraph,
+    archive_path: &Path,
+    solution_mod: &RustPath,
+) -> Vec<RustPathBuf> {
+    let mut used_mods = graph.mods.clone();
+    let mut solutions = Vec::new();
+    collect_rs_files(archive_path, &mut solutions);
+    eprintln!("Found {} archived solutions", solutions.len());
+    for path in solutions {
+        let source_code = read_to_string(path).unwrap();
+        used_mods.extend(record_mods_used_by(src_path, &source_code, solution_mod).mods);
+    }
+    let mut all_mods = Vec::new();
+    let root_path = resolve_mod_path(src_path, &[]).unwrap();
+    collect_declared_mods(&root_path, &[], &mut all_mods);
+    let mut res: Vec<_> = all_mods
+        .into_iter()
+        .filter(|mod_path| mod_path[0] != "plat" && !used_mods.contains(mod_path))
+        .collect();
+    res.sort();
+    res
+}
+
+/// Returns `false` if there is no such mod
+fn process_file(src_path: &Path, cur_mod: &RustPath, graph: &mut ModGraph) -> bool {
+    if graph.mods.contains(cur_mod) {
+        return true;
     }
     if let Some(path) = resolve_mod_path(src_path, cur_mod) {
         eprintln!("Record used mods for {cur_mod:?} in {path:?}");
-        used_mods.insert(cur_mod.iter().cloned().collect());
-        let source_code = read_to_string(path).unwrap();
-        for use_mod in parse_use(&source_code, cur_mod) {
-            for len in 1..=use_mod.len() {
-                process_file(src_path, &use_mod[..len], used_mods);
-            }
-        }
+        graph.mods.insert(cur_mod.iter().cloned().collect());
+        process_ast(src_path, &parse_file_at(&path), cur_mod, graph);
+        true
     } else {
         eprintln!("Ignore non-existing {cur_mod:?}");
-    };
+        false
+    }
+}
+
+fn process_source(src_path: &Path, source_code: &str, cur_mod: &RustPath, graph: &mut ModGraph) {
+    let file = syn::parse_file(source_code).expect("Unable to parse file");
+    process_ast(src_path, &file, cur_mod, graph);
+}
+
+fn process_ast(src_path: &Path, file: &syn::File, cur_mod: &RustPath, graph: &mut ModGraph) {
+    for use_mod in parse_use_file(file, cur_mod) {
+        let mut found_len = 0;
+        for len in 1..=use_mod.len() {
+            if process_file(src_path, &use_mod[..len], graph) {
+                found_len = len;
+            }
+        }
+        if found_len > 0 && use_mod[..found_len] != *cur_mod {
+            graph.edges.insert(ModEdge {
+                from: cur_mod.to_vec(),
+                to: use_mod[..found_len].to_vec(),
+                path: use_mod,
+            });
+        }
+    }
+}
+
+fn collect_declared_mods(file_path: &Path, cur_mod: &RustPath, res: &mut Vec<RustPathBuf>) {
+    for item in parse_file_at(file_path).items {
+        if let Item::Mod(item_mod) = item {
+            if is_cfg_test(&item_mod.attrs) || item_mod.content.is_some() {
+                continue;
+            }
+            let mod_path = join_rust_paths(cur_mod, item_mod.ident.to_string());
+            if let Some(path) = mod_file_path(file_path, &item_mod) {
+                collect_declared_mods(&path, &mod_path, res);
+                res.push(mod_path);
+            }
+        }
+    }
+}
+
+fn collect_rs_files(dir: &Path, res: &mut Vec<std::path::PathBuf>) {
+    for entry in read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_rs_files(&path, res);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            res.push(path);
+        }
+    }
 }
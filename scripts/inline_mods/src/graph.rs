@@ -0,0 +1,122 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::filter::ModGraph;
+use crate::utils::{RustPath, RustPathBuf};
+
+/// Writes the graph as Graphviz DOT or JSON depending on the file extension,
+/// `unused` mods are added as separate nodes
+pub fn write_graph(dest_path: &Path, graph: &ModGraph, unused: &[RustPathBuf]) {
+    let content = match dest_path.extension().and_then(|ext| ext.to_str()) {
+        Some("dot" | "gv") => to_dot(graph, unused),
+        Some("json") => to_json(graph, unused),
+        _ => panic!("unknown graph format of {dest_path:?}, expected .dot, .gv or .json"),
+    };
+    fs::write(dest_path, content).unwrap();
+}
+
+fn mod_name(mod_path: &RustPath) -> String {
+    if mod_path.is_empty() {
+        String::from("crate")
+    } else {
+        mod_path.join("::")
+    }
+}
+
+fn to_dot(graph: &ModGraph, unused: &[RustPathBuf]) -> String {
+    let mut res = String::from("digraph mods {\n");
+    for edge in graph.edges.iter() {
+        writeln!(
+            res,
+            "    {:?} -> {:?} [label={:?}];",
+            mod_name(&edge.from),
+            mod_name(&edge.to),
+            mod_name(&edge.path)
+        )
+        .unwrap();
+    }
+    for mod_path in unused {
+        writeln!(res, "    {:?} [style=dashed];", mod_name(mod_path)).unwrap();
+    }
+    res.push_str("}\n");
+    res
+}
+
+fn to_json(graph: &ModGraph, unused: &[RustPathBuf]) -> String {
+    let mut mods: Vec<_> = graph
+        .mods
+        .iter()
+        .map(|mod_path| mod_name(mod_path))
+        .collect();
+    mods.sort();
+    let edges: Vec<_> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            format!(
+                "{{\"from\": {:?}, \"to\": {:?}, \"path\": {:?}}}",
+                mod_name(&edge.from),
+                mod_name(&edge.to),
+                mod_name(&edge.path)
+            )
+        })
+        .collect();
+    let unused: Vec<_> = unused.iter().map(|mod_path| mod_name(mod_path)).collect();
+    format!(
+        "{{\n  \"mods\": {mods:?},\n  \"edges\": [\n    {}\n  ],\n  \"unused\": {unused:?}\n}}\n",
+        edges.join(",\n    ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::{ModEdge, ModGraph};
+    use crate::utils::RustPathBuf;
+
+    use super::{to_dot, to_json};
+
+    fn split_mod(s: &str) -> RustPathBuf {
+        s.split("::").map(|p| p.to_owned()).collect()
+    }
+
+    fn sample_graph() -> ModGraph {
+        let mut graph = ModGraph::default();
+        graph.mods.extend([split_mod("ds"), split_mod("ds::dsu")]);
+        graph.edges.insert(ModEdge {
+            from: vec![],
+            to: split_mod("ds::dsu"),
+            path: split_mod("ds::dsu::Dsu"),
+        });
+        graph
+    }
+
+    #[test]
+    pub fn graph_dot() {
+        let expected = "digraph mods {
+    \"crate\" -> \"ds::dsu\" [label=\"ds::dsu::Dsu\"];
+    \"ds::seg_tree\" [style=dashed];
+}
+";
+        assert_eq!(
+            to_dot(&sample_graph(), &[split_mod("ds::seg_tree")]),
+            expected
+        );
+    }
+
+    #[test]
+    pub fn graph_json() {
+        let expected = "{
+  \"mods\": [\"ds\", \"ds::dsu\"],
+  \"edges\": [
+    {\"from\": \"crate\", \"to\": \"ds::dsu\", \"path\": \"ds::dsu::Dsu\"}
+  ],
+  \"unused\": [\"ds::seg_tree\"]
+}
+";
+        assert_eq!(
+            to_json(&sample_graph(), &[split_mod("ds::seg_tree")]),
+            expected
+        );
+    }
+}
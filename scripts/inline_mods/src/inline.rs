@@ -1,52 +1,358 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::fs;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
 
-use regex::Regex;
+use syn::{parse_quote, Item};
 
-use crate::filter::record_used_mods;
-use crate::utils::{join_rust_paths, resolve_mod_path, RustPath, RustPathBuf};
+use crate::compat::find_incompatible;
+use crate::dce::eliminate_dead_code;
+use crate::filter::{record_mods_used_by, record_unused_mods, record_used_mods, ModGraph};
+use crate::graph::write_graph;
+use crate::leetcode::{strip_harness, SOLUTION_MOD};
+use crate::minify::{compact, strip_file, Budget};
+use crate::utils::{
+    inline_mod_path, is_cfg_test, item_attrs, join_rust_paths, mod_file_path, modified_time,
+    parse_file_at, read_source_at, resolve_mod_path, take_used_files, RustPath, RustPathBuf,
+};
 
-pub fn inline_main(src_path: &Path, dest_path: &Path) {
-    let used_mods = record_used_mods(src_path);
-    eprintln!("Filter mods: {used_mods:#?}");
-    let lines = process_file(src_path, &[], &used_mods);
-    fs::write(dest_path, lines.join("\n")).unwrap()
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+pub enum Target {
+    Classic,
+    Leetcode,
 }
 
-fn process_file(
-    src_path: &Path,
-    cur_mod: &RustPath,
-    used_mods: &HashSet<RustPathBuf>,
-) -> Vec<String> {
-    let path = resolve_mod_path(src_path, cur_mod).unwrap();
-    eprintln!("Inline {cur_mod:?} from {path:?}");
-    let mod_decl_re: Regex = Regex::new(r"((?:pub )?mod )(\S+);").unwrap();
-    let mut res = Vec::<String>::new();
-    let file = File::open(path).unwrap();
-    for line in BufReader::new(file).lines().map(|r| r.unwrap()) {
-        if line.trim() == "#[cfg(test)]" {
-            continue;
-        }
-        match mod_decl_re.captures(&line) {
-            Some(caps) => {
-                let mod_name = &caps[2];
-                let mod_path = join_rust_paths(cur_mod, mod_name.to_owned());
-                if !used_mods.contains(&mod_path) {
-                    eprintln!("Skip {mod_path:?}");
-                    continue;
-                }
-                res.push(String::from(&caps[1]) + &format!("{mod_name} {{"));
-                for mut mod_line in process_file(&src_path, &mod_path, used_mods) {
-                    mod_line.insert_str(0, "    ");
-                    res.push(mod_line);
+impl Target {
+    fn solution_mod(&self) -> RustPathBuf {
+        let solution_mod: &[&str] = match self {
+            Target::Classic => &["plat", "classic", "solution"],
+            Target::Leetcode => &SOLUTION_MOD,
+        };
+        solution_mod.iter().map(|&s| s.to_owned()).collect()
+    }
+}
+
+pub struct InlineOptions {
+    pub target: Target,
+    pub keep_unused: bool,
+    pub minify: bool,
+    /// Indentation width of the minified code, the printer's 4 spaces are kept if not set
+    pub indent: Option<usize>,
+    pub budget: Option<Budget>,
+    /// Minor version of the judge's Rust 1.x, newer syntax and std APIs are reported
+    pub rust_version: Option<u32>,
+    /// Where to write the mod dependency graph, `.dot` or `.json`
+    pub graph: Option<PathBuf>,
+    /// Directory with past solutions, library mods none of them use are reported
+    pub archive: Option<PathBuf>,
+}
+
+impl Default for InlineOptions {
+    fn default() -> Self {
+        Self {
+            target: Target::Classic,
+            keep_unused: false,
+            minify: false,
+            indent: None,
+            budget: None,
+            rust_version: None,
+            graph: None,
+            archive: None,
+        }
+    }
+}
+
+/// What ended up in the inlined code, used to report the changes between watch runs
+#[derive(PartialEq, Eq)]
+pub struct InlineSummary {
+    pub mods: BTreeSet<String>,
+    pub size: usize,
+}
+
+pub fn inline_main(src_path: &Path, dest_path: &Path, options: &InlineOptions) -> InlineSummary {
+    let (mut file, graph) = match options.target {
+        Target::Classic => {
+            let graph = record_used_mods(src_path);
+            eprintln!("Filter mods: {:#?}", graph.mods);
+            let path = resolve_mod_path(src_path, &[]).unwrap();
+            let mut file = parse_file_at(&path);
+            inline_items(&mut file.items, &path, &[], &graph.mods);
+            (file, graph)
+        }
+        Target::Leetcode => process_leetcode(src_path),
+    };
+    if let Some(archive_path) = &options.archive {
+        let solution_mod = options.target.solution_mod();
+        let unused = record_unused_mods(src_path, &graph, archive_path, &solution_mod);
+        eprintln!("Unused library mods:");
+        for mod_path in unused.iter() {
+            eprintln!("    {}", mod_path.join("::"));
+        }
+        if let Some(graph_path) = &options.graph {
+            write_graph(graph_path, &graph, &unused);
+        }
+    } else if let Some(graph_path) = &options.graph {
+        write_graph(graph_path, &graph, &[]);
+    }
+    if !options.keep_unused {
+        let removed = eliminate_dead_code(&mut file);
+        eprintln!("Removed {removed} unused items");
+    }
+    if let Some(minor) = options.rust_version {
+        report_incompatible(src_path, &file, minor, &options.target);
+    }
+    if options.minify {
+        strip_file(&mut file);
+    }
+    let mut code = prettyplease::unparse(&file);
+    if options.minify {
+        code = compact(&code, options.indent);
+    }
+    fs::write(dest_path, &code).unwrap();
+    match &options.budget {
+        Some(budget) => budget.check(&code),
+        None => eprintln!("Size: {} bytes", code.len()),
+    }
+    let mut mods = BTreeSet::new();
+    collect_mods(&file.items, &[], &mut mods);
+    InlineSummary {
+        mods,
+        size: code.len(),
+    }
+}
+
+/// Re-inlines whenever one of the files read by the previous run is modified or removed,
+/// a failed run is reported and waits for the next change
+pub fn watch(src_path: &Path, dest_path: &Path, options: &InlineOptions) {
+    let mut prev = None;
+    loop {
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            inline_main(src_path, dest_path, options)
+        }));
+        let watched = take_used_files();
+        match res {
+            Ok(summary) => {
+                match &prev {
+                    Some(prev) => eprintln!("{}", summary_diff(prev, &summary)),
+                    None => eprintln!(
+                        "Inlined {} mods, {} bytes",
+                        summary.mods.len(),
+                        summary.size
+                    ),
                 }
-                res.extend_from_slice(&[String::from("}"), String::from("")]);
+                prev = Some(summary);
             }
-            None => res.push(line),
+            Err(_) => eprintln!("Inlining failed, waiting for changes"),
         }
+        eprintln!("Watching {} files", watched.len());
+        while watched
+            .iter()
+            .all(|(path, modified)| modified_time(path) == Some(*modified))
+        {
+            thread::sleep(WATCH_INTERVAL);
+        }
+    }
+}
+
+fn summary_diff(prev: &InlineSummary, cur: &InlineSummary) -> String {
+    let mut res = Vec::new();
+    let added: Vec<_> = cur.mods.difference(&prev.mods).cloned().collect();
+    if !added.is_empty() {
+        res.push(format!("added {}", added.join(", ")));
+    }
+    let removed: Vec<_> = prev.mods.difference(&cur.mods).cloned().collect();
+    if !removed.is_empty() {
+        res.push(format!("removed {}", removed.join(", ")));
+    }
+    let delta = cur.size as i64 - prev.size as i64;
+    res.push(format!("{delta:+} bytes, {} bytes total", cur.size));
+    res.join("; ")
+}
+
+fn collect_mods(items: &[Item], cur_mod: &RustPath, res: &mut BTreeSet<String>) {
+    for item in items {
+        if let Item::Mod(syn::ItemMod {
+            ident,
+            content: Some((_, content)),
+            ..
+        }) = item
+        {
+            let mod_path = join_rust_paths(cur_mod, ident.to_string());
+            collect_mods(content, &mod_path, res);
+            res.insert(mod_path.join("::"));
+        }
+    }
+}
+
+fn report_incompatible(src_path: &Path, file: &syn::File, minor: u32, target: &Target) {
+    let hits = find_incompatible(file, minor);
+    for hit in hits.iter() {
+        let path = source_file(src_path, &hit.mod_path, target);
+        eprintln!(
+            "{}:{}:{}: {} requires Rust 1.{}",
+            path.display(),
+            hit.line,
+            hit.column,
+            hit.feature,
+            hit.since
+        );
+    }
+    eprintln!("Found {} incompatibilities with Rust 1.{minor}", hits.len());
+}
+
+/// Inline mods are in the same file as their closest parent with a file,
+/// for leetcode the root items are the solution ones
+fn source_file(src_path: &Path, mod_path: &RustPath, target: &Target) -> PathBuf {
+    let solution_mod = target.solution_mod();
+    let mod_path = match target {
+        Target::Leetcode if mod_path.is_empty() => &solution_mod,
+        _ => mod_path,
+    };
+    (0..=mod_path.len())
+        .rev()
+        .find_map(|len| resolve_mod_path(src_path, &mod_path[..len]))
+        .unwrap()
+}
+
+/// Only the solution items and the library mods they use,
+/// all of them are placed at the crate root so that `crate::` paths stay valid
+fn process_leetcode(src_path: &Path) -> (syn::File, ModGraph) {
+    let solution_mod = Target::Leetcode.solution_mod();
+    let path = resolve_mod_path(src_path, &solution_mod).unwrap();
+    let solution_code = strip_harness(&read_source_at(&path), &solution_mod);
+    let graph = record_mods_used_by(src_path, &solution_code, &solution_mod);
+    eprintln!("Filter mods: {:#?}", graph.mods);
+    let mut file = syn::parse_file(&solution_code).unwrap();
+    let root_path = resolve_mod_path(src_path, &[]).unwrap();
+    let mut root_items = parse_file_at(&root_path).items;
+    root_items.retain(|item| matches!(item, Item::Mod(_)));
+    inline_items(&mut root_items, &root_path, &[], &graph.mods);
+    for mut item in root_items {
+        if let Item::Mod(item_mod) = &mut item {
+            item_mod
+                .attrs
+                .push(parse_quote!(#[allow(dead_code, unused_imports, unused_macros)]));
+        }
+        file.items.push(item);
+    }
+    (file, graph)
+}
+
+/// Replaces `mod name;` declarations with the contents of their files,
+/// unused mods and `#[cfg(test)]` items are dropped
+fn inline_items(
+    items: &mut Vec<Item>,
+    file_path: &Path,
+    cur_mod: &RustPath,
+    used_mods: &HashSet<RustPathBuf>,
+) {
+    items.retain_mut(|item| {
+        if is_cfg_test(item_attrs(item)) {
+            return false;
+        }
+        let Item::Mod(item_mod) = item else {
+            return true;
+        };
+        let mod_path = join_rust_paths(cur_mod, item_mod.ident.to_string());
+        let inline_path = inline_mod_path(file_path, item_mod);
+        if let Some((_, content)) = &mut item_mod.content {
+            inline_items(content, &inline_path, &mod_path, used_mods);
+            return true;
+        }
+        if !used_mods.contains(&mod_path) {
+            eprintln!("Skip {mod_path:?}");
+            return false;
+        }
+        let path = mod_file_path(file_path, item_mod).unwrap();
+        eprintln!("Inline {mod_path:?} from {path:?}");
+        let mut file = parse_file_at(&path);
+        inline_items(&mut file.items, &path, &mod_path, used_mods);
+        item_mod.attrs.retain(|attr| !attr.path().is_ident("path"));
+        item_mod.attrs.extend(file.attrs);
+        item_mod.content = Some((Default::default(), file.items));
+        item_mod.semi = None;
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{inline_main, summary_diff, InlineOptions, InlineSummary};
+
+    #[test]
+    pub fn inline_mod_declarations() {
+        let dir = std::env::temp_dir().join("inline_mods_test");
+        let _ = fs::remove_dir_all(&dir);
+        let files = [
+            (
+                "main.rs",
+                "use crate::ds::dsu::Dsu;\nuse crate::extra::Extra;\n\npub(crate) mod ds;\n#[path = \"other/extra_impl.rs\"]\nmod extra;\nmod unused;\n\nfn main() {\n    let Some(_) = Some(1) else { return };\n    Dsu::new(Extra);\n}\n",
+            ),
+            (
+                "ds/mod.rs",
+                "#[cfg(\n    test\n)]\nmod tests;\n\n#[allow(\n    dead_code\n)]\npub mod dsu;\n",
+            ),
+            (
+                "ds/dsu.rs",
+                "#![allow(unused_imports)]\npub struct Dsu;\n\nimpl Dsu {\n    pub fn new<T>(_: T) {}\n}\n\n#[cfg(test)]\nmod tests {\n    use super::Dsu;\n}\n",
+            ),
+            ("ds/tests.rs", "#[test]\nfn it_works() {}\n"),
+            ("other/extra_impl.rs", "pub struct Extra;\n"),
+            ("unused.rs", "pub struct Unused;\n"),
+        ];
+        for (name, content) in files {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let dest = dir.join("out.rs");
+        let options = InlineOptions {
+            keep_unused: true,
+            ..Default::default()
+        };
+        inline_main(&dir, &dest, &options);
+        let expected = "use crate::ds::dsu::Dsu;
+use crate::extra::Extra;
+pub(crate) mod ds {
+    #[allow(dead_code)]
+    pub mod dsu {
+        #![allow(unused_imports)]
+        pub struct Dsu;
+        impl Dsu {
+            pub fn new<T>(_: T) {}
+        }
+    }
+}
+mod extra {
+    pub struct Extra;
+}
+fn main() {
+    let Some(_) = Some(1) else { return };
+    Dsu::new(Extra);
+}
+";
+        assert_eq!(fs::read_to_string(dest).unwrap(), expected);
+    }
+
+    #[test]
+    pub fn watch_summary_diff() {
+        let summary = |mods: &[&str], size| InlineSummary {
+            mods: mods.iter().map(|&m| m.to_owned()).collect(),
+            size,
+        };
+        let prev = summary(&["ds", "ds::dsu", "io"], 1000);
+        assert_eq!(
+            summary_diff(&prev, &summary(&["ds", "ds::seg_tree", "io"], 1250)),
+            "added ds::seg_tree; removed ds::dsu; +250 bytes, 1250 bytes total"
+        );
+        assert_eq!(
+            summary_diff(&prev, &summary(&["ds", "ds::dsu", "io"], 990)),
+            "-10 bytes, 990 bytes total"
+        );
     }
-    res
 }
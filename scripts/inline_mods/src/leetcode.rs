@@ -0,0 +1,100 @@
+use syn::spanned::Spanned;
+use syn::Item;
+
+use crate::use_parser::parse_use_item;
+use crate::utils::RustPath;
+
+pub const SOLUTION_MOD: [&str; 3] = ["plat", "leetcode", "solution"];
+
+const HARNESS_MACROS: [&str; 4] = ["check_mode", "with_input", "with_design_input", "tree"];
+
+/// Drops the local harness (`exec`, `TEST_COUNT`, `check_mode!`, `struct Solution` and their imports)
+/// from the solution source, the remaining items are kept on their original lines
+pub fn strip_harness(source_code: &str, cur_mod: &RustPath) -> String {
+    let file = syn::parse_file(source_code).expect("Unable to parse file");
+    let harness_lines: Vec<_> = file
+        .items
+        .iter()
+        .filter(|item| is_harness(item, cur_mod))
+        .map(|item| {
+            let span = item.span();
+            span.start().line..=span.end().line
+        })
+        .collect();
+    let res: Vec<_> = source_code
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if harness_lines.iter().any(|rng| rng.contains(&(i + 1))) {
+                ""
+            } else {
+                line
+            }
+        })
+        .collect();
+    res.join("\n")
+}
+
+fn is_harness(item: &Item, cur_mod: &RustPath) -> bool {
+    match item {
+        Item::Use(item_use) => {
+            let paths = parse_use_item(item_use, cur_mod);
+            let harness_cnt = paths.iter().filter(|path| is_harness_path(path)).count();
+            if harness_cnt > 0 && harness_cnt < paths.len() {
+                panic!("harness and library imports should be split: {paths:?}");
+            }
+            harness_cnt > 0
+        }
+        Item::Struct(item_struct) => item_struct.ident == "Solution",
+        Item::Fn(item_fn) => item_fn.sig.ident == "exec",
+        Item::Const(item_const) => item_const.ident == "TEST_COUNT",
+        Item::Macro(item_macro) => item_macro.mac.path.is_ident("check_mode"),
+        _ => false,
+    }
+}
+
+fn is_harness_path(path: &RustPath) -> bool {
+    path.starts_with(&["plat".to_owned(), "leetcode".to_owned()])
+        || (path.len() == 1 && HARNESS_MACROS.contains(&path[0].as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{strip_harness, SOLUTION_MOD};
+
+    #[test]
+    pub fn strip_harness_basic() {
+        let src = "use std::rc::Rc;
+use crate::plat::leetcode::defs::TreeNode;
+
+impl Solution {
+    pub fn echo(n: i32) -> i32 {
+        n
+    }
+}
+
+use crate::{check_mode, plat::leetcode::io::{InputReader, ToLeetcodeValueNode}, with_input};
+use crate::ds::dsu::Dsu;
+pub const TEST_COUNT: usize = 1;
+check_mode!(print);
+
+pub fn exec(r: &mut InputReader) -> impl ToLeetcodeValueNode {
+    with_input!{ r => echo(n) }
+}
+
+struct Solution;";
+        let expected = "use std::rc::Rc;
+impl Solution {
+    pub fn echo(n: i32) -> i32 {
+        n
+    }
+}
+use crate::ds::dsu::Dsu;";
+        let cur_mod: Vec<_> = SOLUTION_MOD.iter().map(|s| s.to_string()).collect();
+        let actual = strip_harness(src, &cur_mod);
+        let non_empty: Vec<_> = actual.lines().filter(|line| !line.is_empty()).collect();
+        assert_eq!(non_empty.join("\n"), expected);
+        assert_eq!(actual.split('\n').count(), src.split('\n').count());
+        assert_eq!(actual.lines().nth(10), Some("use crate::ds::dsu::Dsu;"));
+    }
+}
@@ -1,20 +1,71 @@
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use inline::inline_main;
+use compat::parse_rust_version;
+use inline::{inline_main, watch, InlineOptions, Target};
+use minify::Budget;
 
+pub mod compat;
+pub mod dce;
 pub mod filter;
+pub mod graph;
 pub mod inline;
+pub mod leetcode;
+pub mod minify;
 pub mod use_parser;
 pub mod utils;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if let [_, arg1, arg2] = &args[..] {
+    let mut options = InlineOptions::default();
+    let mut paths = Vec::new();
+    let mut watch_mode = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--target" => {
+                options.target = match args.next().as_deref() {
+                    Some("classic") => Target::Classic,
+                    Some("leetcode") => Target::Leetcode,
+                    other => panic!("unknown target {other:?}, expected classic or leetcode"),
+                }
+            }
+            "--keep-unused" => options.keep_unused = true,
+            "--minify" => options.minify = true,
+            "--indent" => options.indent = Some(parse_number(&arg, args.next())),
+            "--judge" => {
+                let judge = args.next().expect("--judge expects a judge name");
+                options.budget = Some(Budget::for_judge(&judge));
+            }
+            "--budget" => options.budget = Some(Budget::custom(parse_number(&arg, args.next()))),
+            "--rust-version" => {
+                let version = args.next().expect("--rust-version expects a version");
+                options.rust_version = Some(parse_rust_version(&version));
+            }
+            "--graph" => options.graph = Some(expect_path(&arg, args.next())),
+            "--archive" => options.archive = Some(expect_path(&arg, args.next())),
+            "--watch" => watch_mode = true,
+            _ => paths.push(arg),
+        }
+    }
+    if let [arg1, arg2] = &paths[..] {
         let src_path = Path::new(arg1);
         let dest_path = Path::new(arg2);
-        inline_main(src_path, dest_path);
+        if watch_mode {
+            watch(src_path, dest_path, &options);
+        } else {
+            inline_main(src_path, dest_path, &options);
+        }
     } else {
-        panic!("expected 2 args, got {:?}", &args[1..]);
+        panic!("expected 2 args, got {:?}", paths);
     }
 }
+
+fn parse_number(flag: &str, value: Option<String>) -> usize {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("{flag} expects a number"))
+}
+
+fn expect_path(flag: &str, value: Option<String>) -> PathBuf {
+    PathBuf::from(value.unwrap_or_else(|| panic!("{flag} expects a path")))
+}
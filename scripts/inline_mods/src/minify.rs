@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::Item;
+
+use crate::utils::{is_cfg_test, item_attrs};
+
+/// Source size limits of the judges, in bytes
+const JUDGE_BUDGETS: [(&str, usize); 3] = [
+    ("codeforces", 64 * 1024),
+    ("atcoder", 512 * 1024),
+    ("codechef", 50000),
+];
+
+pub struct Budget {
+    pub name: String,
+    pub bytes: usize,
+}
+
+impl Budget {
+    pub fn for_judge(judge: &str) -> Self {
+        let (name, bytes) = JUDGE_BUDGETS
+            .iter()
+            .find(|(name, _)| *name == judge)
+            .unwrap_or_else(|| {
+                panic!("unknown judge {judge:?}, expected one of {JUDGE_BUDGETS:?}")
+            });
+        Self {
+            name: name.to_string(),
+            bytes: *bytes,
+        }
+    }
+
+    pub fn custom(bytes: usize) -> Self {
+        Self {
+            name: String::from("custom"),
+            bytes,
+        }
+    }
+
+    /// Panics if the code doesn't fit, so that an oversized submission is never missed
+    pub fn check(&self, code: &str) {
+        let size = code.len();
+        eprintln!(
+            "Size: {size} bytes, {:.1}% of {} budget {} bytes",
+            size as f64 * 100.0 / self.bytes as f64,
+            self.name,
+            self.bytes
+        );
+        if size > self.bytes {
+            panic!(
+                "{} budget exceeded by {} bytes",
+                self.name,
+                size - self.bytes
+            );
+        }
+    }
+}
+
+/// Drops doc comments (including the ones inside macro bodies) and `#[cfg(test)]` items
+pub fn strip_file(file: &mut syn::File) {
+    let tokens = strip_doc_attrs(file.to_token_stream());
+    *file = syn::parse2(tokens).expect("Unable to parse code without doc comments");
+    strip_test_items(&mut file.items);
+}
+
+fn strip_doc_attrs(tokens: TokenStream) -> TokenStream {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    let mut res = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let attr_len = match &tokens[i..] {
+            [TokenTree::Punct(hash), TokenTree::Group(group), ..]
+                if hash.as_char() == '#' && is_doc_group(group) =>
+            {
+                2
+            }
+            [TokenTree::Punct(hash), TokenTree::Punct(bang), TokenTree::Group(group), ..]
+                if hash.as_char() == '#' && bang.as_char() == '!' && is_doc_group(group) =>
+            {
+                3
+            }
+            _ => 0,
+        };
+        if attr_len > 0 {
+            i += attr_len;
+            continue;
+        }
+        res.push(match &tokens[i] {
+            TokenTree::Group(group) => {
+                let mut stripped = Group::new(group.delimiter(), strip_doc_attrs(group.stream()));
+                stripped.set_span(group.span());
+                TokenTree::Group(stripped)
+            }
+            token => token.clone(),
+        });
+        i += 1;
+    }
+    res.into_iter().collect()
+}
+
+fn is_doc_group(group: &Group) -> bool {
+    matches!(
+        group.stream().into_iter().next(),
+        Some(TokenTree::Ident(ident)) if ident == "doc"
+    )
+}
+
+fn strip_test_items(items: &mut Vec<Item>) {
+    items.retain_mut(|item| {
+        if is_cfg_test(item_attrs(item)) {
+            return false;
+        }
+        if let Item::Mod(syn::ItemMod {
+            content: Some((_, content)),
+            ..
+        }) = item
+        {
+            strip_test_items(content);
+        }
+        true
+    });
+}
+
+/// Removes blank lines and optionally replaces the 4-space indentation with `indent` spaces,
+/// lines inside multiline string literals are kept as they are
+pub fn compact(code: &str, indent: Option<usize>) -> String {
+    let literal_lines = multiline_literal_lines(code);
+    let mut res = String::new();
+    for (i, line) in code.lines().enumerate() {
+        if literal_lines.contains(&(i + 1)) {
+            res += line;
+            res.push('\n');
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let content = line.trim_start_matches(' ');
+        let spaces = line.len() - content.len();
+        if let Some(indent) = indent {
+            res += &" ".repeat(spaces / 4 * indent + spaces % 4);
+        } else {
+            res += &line[..spaces];
+        }
+        res += content;
+        res.push('\n');
+    }
+    res
+}
+
+/// 1-based numbers of the lines that continue a string literal started on a previous line
+fn multiline_literal_lines(code: &str) -> HashSet<usize> {
+    fn collect(tokens: TokenStream, out: &mut HashSet<usize>) {
+        for token in tokens {
+            match token {
+                TokenTree::Literal(literal) => {
+                    let span = literal.span();
+                    out.extend(span.start().line + 1..=span.end().line);
+                }
+                TokenTree::Group(group) => collect(group.stream(), out),
+                _ => {}
+            }
+        }
+    }
+    let mut res = HashSet::new();
+    collect(TokenStream::from_str(code).unwrap(), &mut res);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compact, strip_file, Budget};
+
+    #[test]
+    pub fn minify_strips_docs_and_tests() {
+        let src = "//! Crate docs
+mod io {
+    /// Reads input
+    ///
+    /// # Examples
+    /// ```
+    /// input! { io => n: usize }
+    /// ```
+    #[macro_export]
+    macro_rules! input {
+        () => {
+            /// Inner docs
+            #[doc = \"more\"]
+            fn f() {}
+        };
+    }
+
+    #[cfg(test)]
+    mod tests {}
+}";
+        let mut file = syn::parse_file(src).unwrap();
+        strip_file(&mut file);
+        let expected = "mod io {
+    #[macro_export]
+    macro_rules! input {
+        () => {
+            fn f() {}
+        };
+    }
+}
+";
+        assert_eq!(prettyplease::unparse(&file), expected);
+    }
+
+    #[test]
+    pub fn compact_keeps_multiline_literals() {
+        let src = "fn f() {
+
+    let s = \"a
+        b\";
+    if true {
+        g();
+    }
+}
+";
+        let expected = "fn f() {
+ let s = \"a
+        b\";
+ if true {
+  g();
+ }
+}
+";
+        assert_eq!(compact(src, Some(1)), expected);
+        assert_eq!(compact(src, None), src.replacen("\n\n", "\n", 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "custom budget exceeded by 2 bytes")]
+    pub fn budget_exceeded() {
+        Budget::custom(4).check("fn f");
+        Budget::for_judge("codeforces").check("fn f()");
+        Budget::custom(4).check("fn f()");
+    }
+}
@@ -1,9 +1,12 @@
 use std::collections::HashSet;
 
-use syn::visit::Visit;
+use proc_macro2::{TokenStream, TokenTree};
+use syn::visit::{self, Visit};
 use syn::UseTree;
 
-use crate::utils::{parent_rust_path, RustPath, RustPathBuf};
+use crate::utils::{
+    is_cfg_test, item_attrs, join_rust_paths, parent_rust_path, RustPath, RustPathBuf,
+};
 
 struct UseVisitor<'a> {
     cur_mod: &'a RustPath,
@@ -12,35 +15,91 @@ struct UseVisitor<'a> {
 }
 
 impl<'a> UseVisitor<'a> {
+    fn new(cur_mod: &'a RustPath) -> Self {
+        Self {
+            cur_mod,
+            use_statements: HashSet::new(),
+            stack: Vec::new(),
+        }
+    }
+
     fn record_use(&mut self, name: Option<String>) {
-        let mut path = if let Some(first) = self.stack.first() {
-            match first.as_str() {
-                "crate" => vec![],
-                "self" => self.cur_mod.to_vec(),
-                "super" => parent_rust_path(self.cur_mod).unwrap().to_vec(),
-                "std" => return,
-                other => {
-                    panic!("use should not start with {other:?}: {:?}", self.stack);
-                }
-            }
-        } else {
+        let mut segments = self.stack.clone();
+        segments.extend(name);
+        self.record_path(&segments, true);
+    }
+
+    /// `use` paths without a special root are relative to the current mod,
+    /// other paths are recorded only if they start with `crate`, `self` or `super`
+    fn record_path(&mut self, segments: &[String], allow_relative: bool) {
+        let Some((first, rest)) = segments.split_first() else {
             return;
         };
-        for ident in self.stack.iter().skip(1) {
+        let mut path = match first.as_str() {
+            "crate" => vec![],
+            "self" => self.cur_mod.to_vec(),
+            "super" => parent_rust_path(self.cur_mod).unwrap().to_vec(),
+            "std" | "core" | "alloc" => return,
+            other if allow_relative => join_rust_paths(self.cur_mod, other.to_owned()),
+            _ => return,
+        };
+        for ident in rest {
             if ident == "super" {
                 path.pop();
             } else {
                 path.push(ident.to_owned());
             }
         }
-        if let Some(name) = name {
-            path.push(name);
-        }
         self.use_statements.insert(path);
     }
+
+    /// Macro arguments and `macro_rules!` bodies are not parsed by `syn`,
+    /// so paths are looked up directly in the tokens: `$crate::a::b`, `crate::a`, `super::a`, ...
+    /// (`$crate` is split into `$` and `crate`)
+    fn record_token_paths(&mut self, tokens: TokenStream) {
+        let tokens: Vec<_> = tokens.into_iter().collect();
+        let mut i = 0;
+        while i < tokens.len() {
+            match &tokens[i] {
+                TokenTree::Group(group) => self.record_token_paths(group.stream()),
+                TokenTree::Ident(ident)
+                    if ["crate", "self", "super"].contains(&ident.to_string().as_str()) =>
+                {
+                    let mut segments = vec![ident.to_string()];
+                    while let Some(next) = path_continuation(&tokens[i + 1..]) {
+                        segments.push(next);
+                        i += 3;
+                    }
+                    if segments.len() > 1 {
+                        self.record_path(&segments, false);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Matches `:: ident` at the start of the tokens
+fn path_continuation(tokens: &[TokenTree]) -> Option<String> {
+    match tokens {
+        [TokenTree::Punct(p1), TokenTree::Punct(p2), TokenTree::Ident(ident), ..]
+            if p1.as_char() == ':' && p2.as_char() == ':' =>
+        {
+            Some(ident.to_string())
+        }
+        _ => None,
+    }
 }
 
 impl<'ast, 'a> Visit<'ast> for UseVisitor<'a> {
+    fn visit_item(&mut self, item: &'ast syn::Item) {
+        if !is_cfg_test(item_attrs(item)) {
+            visit::visit_item(self, item);
+        }
+    }
+
     fn visit_use_tree(&mut self, tree: &'ast syn::UseTree) {
         match tree {
             UseTree::Path(path) => {
@@ -64,16 +123,39 @@ impl<'ast, 'a> Visit<'ast> for UseVisitor<'a> {
             }
         }
     }
+
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if path.leading_colon.is_none() && path.segments.len() > 1 {
+            let segments: Vec<_> = path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            self.record_path(&segments, false);
+        }
+        visit::visit_path(self, path);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        self.record_token_paths(mac.tokens.clone());
+        visit::visit_macro(self, mac);
+    }
 }
 
 pub fn parse_use(source_code: &str, cur_mod: &RustPath) -> HashSet<RustPathBuf> {
     let file = syn::parse_file(&source_code).expect("Unable to parse file");
-    let mut visitor = UseVisitor {
-        cur_mod,
-        use_statements: HashSet::new(),
-        stack: Vec::new(),
-    };
-    visitor.visit_file(&file);
+    parse_use_file(&file, cur_mod)
+}
+
+pub fn parse_use_file(file: &syn::File, cur_mod: &RustPath) -> HashSet<RustPathBuf> {
+    let mut visitor = UseVisitor::new(cur_mod);
+    visitor.visit_file(file);
+    visitor.use_statements
+}
+
+pub fn parse_use_item(item: &syn::ItemUse, cur_mod: &RustPath) -> HashSet<RustPathBuf> {
+    let mut visitor = UseVisitor::new(cur_mod);
+    visitor.visit_item_use(item);
     visitor.use_statements
 }
 
@@ -116,6 +198,51 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_use_other_roots() {
+        check("use core::cmp::max;", "ds::seg_tree", &[]);
+        check("use alloc::vec::Vec;", "ds::seg_tree", &[]);
+        check("use seg_tree_values::*;", "ds", &["ds::seg_tree_values"]);
+    }
+
+    #[test]
+    pub fn parse_use_skips_tests() {
+        check(
+            "#[cfg(test)]\nmod tests {\n    use crate::ds::dsu::Dsu;\n}",
+            "ds",
+            &[],
+        );
+    }
+
+    #[test]
+    pub fn parse_paths_and_macros() {
+        check(
+            "fn f() { let v = crate::ds::dsu::Dsu::new(self.n); }",
+            "plat::classic::solution",
+            &["ds::dsu::Dsu::new"],
+        );
+        check(
+            "crate::ds::seg_tree_values::seg_tree_value_sum!(Sum, i64);",
+            "plat::classic::solution",
+            &["ds::seg_tree_values::seg_tree_value_sum"],
+        );
+        check(
+            "fn f() { with_input!{ r => super::io::echo(n) } }",
+            "plat::leetcode::solution",
+            &["plat::leetcode::io::echo"],
+        );
+        check(
+            "macro_rules! m { () => { $crate::utils::io::read_value!(x) }; }",
+            "plat::classic::io",
+            &["utils::io::read_value"],
+        );
+        check(
+            "fn f() { let v = Vec::<i32>::new(); std::mem::swap(a, b); }",
+            "ds",
+            &[],
+        );
+    }
+
     fn check(src: &str, cur_mod: &str, expected: &[&str]) {
         let actual = parse_use(src, &split_mod(cur_mod));
         let expected: HashSet<_> = expected.into_iter().map(|&s| split_mod(s)).collect();
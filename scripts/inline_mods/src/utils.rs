@@ -1,4 +1,10 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{self, read_to_string};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use syn::{Attribute, Expr, ExprLit, Item, ItemMod, Lit, Meta};
 
 pub type RustPathBuf = Vec<String>;
 pub type RustPath = [String];
@@ -13,20 +19,201 @@ pub fn parent_rust_path(path: &RustPath) -> Option<&RustPath> {
     path.len().checked_sub(1).map(|n| &path[0..n])
 }
 
+/// Follows the `mod` declarations starting from `main.rs`, so `#[path]` attributes are respected
 pub fn resolve_mod_path(src_path: &Path, mod_path: &RustPath) -> Option<PathBuf> {
-    let cands = if let Some(last) = mod_path.last() {
-        let mut buf = src_path.to_owned();
-        for v in mod_path.iter().rev().skip(1).rev() {
-            buf.push(v);
-        }
+    let Some((last, parent)) = mod_path.split_last() else {
+        let root = src_path.join("main.rs");
+        return root.exists().then_some(root);
+    };
+    let parent_path = resolve_mod_path(src_path, parent)?;
+    parse_file_at(&parent_path)
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Mod(item_mod) if item_mod.ident == last && item_mod.content.is_none() => {
+                mod_file_path(&parent_path, item_mod)
+            }
+            _ => None,
+        })
+}
 
-        let mut mod_buf = buf.clone();
-        mod_buf.push(last.to_owned());
-        mod_buf.push("mod.rs");
-        buf.push(last.to_owned() + ".rs");
-        vec![mod_buf, buf]
+/// File of the `mod name;` declared in `parent_path`
+pub fn mod_file_path(parent_path: &Path, item_mod: &ItemMod) -> Option<PathBuf> {
+    let dir = parent_path.parent().unwrap();
+    let cands = if let Some(path) = path_attr(&item_mod.attrs) {
+        vec![dir.join(path)]
     } else {
-        vec![src_path.join("main.rs").to_owned()]
+        let dir = if is_mod_rs(parent_path) {
+            dir.to_owned()
+        } else {
+            dir.join(parent_path.file_stem().unwrap())
+        };
+        let name = item_mod.ident.to_string();
+        vec![dir.join(&name).join("mod.rs"), dir.join(name + ".rs")]
     };
-    cands.into_iter().filter(|path| path.exists()).next()
+    cands.into_iter().find(|path| path.exists())
+}
+
+/// Declarations inside an inline `mod name { ... }` resolve as if it was in `name/mod.rs`
+pub fn inline_mod_path(parent_path: &Path, item_mod: &ItemMod) -> PathBuf {
+    let mut path = parent_path.parent().unwrap().to_owned();
+    if !is_mod_rs(parent_path) {
+        path.push(parent_path.file_stem().unwrap());
+    }
+    path.push(item_mod.ident.to_string());
+    path.push("mod.rs");
+    path
+}
+
+fn is_mod_rs(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|name| name.to_str()),
+        Some("main.rs" | "lib.rs" | "mod.rs")
+    )
+}
+
+struct CachedFile {
+    modified: SystemTime,
+    source_code: String,
+    /// Parse errors are cached too, so that a broken file is watched until it's fixed
+    file: Result<syn::File, String>,
+    used: bool,
+}
+
+thread_local! {
+    /// Parsed sources, an entry is valid while the file's modification time stays the same
+    static PARSE_CACHE: RefCell<HashMap<PathBuf, CachedFile>> = RefCell::new(HashMap::new());
+}
+
+fn with_cached_file<T>(path: &Path, f: impl FnOnce(&CachedFile) -> T) -> T {
+    let modified = modified_time(path).unwrap_or_else(|| panic!("Unable to read {path:?}"));
+    let res = PARSE_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let stale = !matches!(cache.get(path), Some(cached) if cached.modified == modified);
+        if stale {
+            let source_code = read_to_string(path).unwrap();
+            let file = syn::parse_file(&source_code).map_err(|err| err.to_string());
+            let cached = CachedFile {
+                modified,
+                source_code,
+                file,
+                used: false,
+            };
+            cache.insert(path.to_owned(), cached);
+        }
+        let cached = cache.get_mut(path).unwrap();
+        cached.used = true;
+        cached
+            .file
+            .as_ref()
+            .map(|_| f(cached))
+            .map_err(|err| err.clone())
+    });
+    res.unwrap_or_else(|err| panic!("Unable to parse {path:?}: {err}"))
+}
+
+pub fn parse_file_at(path: &Path) -> syn::File {
+    with_cached_file(path, |cached| cached.file.clone().unwrap())
+}
+
+pub fn read_source_at(path: &Path) -> String {
+    with_cached_file(path, |cached| cached.source_code.clone())
+}
+
+/// Files read through the cache since the previous call, with their modification times
+pub fn take_used_files() -> Vec<(PathBuf, SystemTime)> {
+    PARSE_CACHE.with(|cache| {
+        let mut res = Vec::new();
+        for (path, cached) in cache.borrow_mut().iter_mut() {
+            if cached.used {
+                cached.used = false;
+                res.push((path.clone(), cached.modified));
+            }
+        }
+        res
+    })
+}
+
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+pub fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(meta) if meta.path.is_ident("path") => match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Some(lit.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+pub fn is_cfg_test(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| match &attr.meta {
+        Meta::List(meta) if meta.path.is_ident("cfg") => meta
+            .parse_args::<syn::Path>()
+            .is_ok_and(|path| path.is_ident("test")),
+        _ => false,
+    })
+}
+
+pub fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Const(item) => &item.attrs,
+        Item::Enum(item) => &item.attrs,
+        Item::ExternCrate(item) => &item.attrs,
+        Item::Fn(item) => &item.attrs,
+        Item::ForeignMod(item) => &item.attrs,
+        Item::Impl(item) => &item.attrs,
+        Item::Macro(item) => &item.attrs,
+        Item::Mod(item) => &item.attrs,
+        Item::Static(item) => &item.attrs,
+        Item::Struct(item) => &item.attrs,
+        Item::Trait(item) => &item.attrs,
+        Item::TraitAlias(item) => &item.attrs,
+        Item::Type(item) => &item.attrs,
+        Item::Union(item) => &item.attrs,
+        Item::Use(item) => &item.attrs,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::time::{Duration, SystemTime};
+
+    use super::{parse_file_at, take_used_files};
+
+    #[test]
+    pub fn parse_cache_tracks_modifications() {
+        let path = std::env::temp_dir().join("inline_mods_cache_test.rs");
+        fs::write(&path, "fn a() {}\n").unwrap();
+        assert_eq!(parse_file_at(&path).items.len(), 1);
+        let used = take_used_files();
+        assert!(used.iter().any(|(used_path, _)| *used_path == path));
+        assert!(take_used_files().is_empty());
+
+        // Same modification time, the cached parse is returned
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        fs::write(&path, "fn a() {}\nfn b() {}\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert_eq!(parse_file_at(&path).items.len(), 1);
+
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(parse_file_at(&path).items.len(), 2);
+        let _ = fs::remove_file(&path);
+    }
 }
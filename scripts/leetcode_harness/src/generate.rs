@@ -0,0 +1,282 @@
+use crate::signature::{parse_snippet, strip_trailing_comment, Method, ParamType, Snippet};
+
+pub fn generate_solution(snippet: &str) -> Result<String, String> {
+    let mut imports = vec![
+        "check_mode",
+        "plat::leetcode::io::{InputReader, ToLeetcodeValueNode}",
+    ];
+    if snippet.contains("TreeNode") {
+        imports.push("plat::leetcode::defs::TreeNode");
+    }
+    let (exec_body, struct_decl) = match parse_snippet(snippet)? {
+        Snippet::Solution(method) => {
+            let is_simple = method
+                .params
+                .iter()
+                .all(|param| matches!(param.ty, ParamType::Value));
+            if is_simple {
+                imports.push("with_input");
+                (with_input_call(&method), "\nstruct Solution;\n")
+            } else {
+                (explicit_call(&method), "\nstruct Solution;\n")
+            }
+        }
+        Snippet::Design { class, methods } => {
+            imports.push("with_design_input");
+            (with_design_input_call(&class, &methods)?, "")
+        }
+    };
+    imports.sort();
+    Ok(format!(
+        "{}\n\nuse crate::{{{}}};\npub const TEST_COUNT: usize = 1;\ncheck_mode!(print);\n\n\
+         pub fn exec(r: &mut InputReader) -> impl ToLeetcodeValueNode {{\n{exec_body}}}\n{struct_decl}",
+        strip_trailing_comment(snippet).trim_start(),
+        imports.join(", "),
+    ))
+}
+
+fn with_input_call(method: &Method) -> String {
+    format!(
+        "    with_input!{{ r => {}({}) }}\n",
+        method.name,
+        param_names(method).join(", ")
+    )
+}
+
+/// Reference parameters can't be passed by `with_input!`, so they are read one by one.
+/// Mutated parameters are returned when the method itself doesn't return anything.
+fn explicit_call(method: &Method) -> String {
+    let mut ret = String::new();
+    let mut args = Vec::new();
+    let mut mutated = None;
+    for param in method.params.iter() {
+        let name = &param.name;
+        match &param.ty {
+            ParamType::Value => {
+                ret += &format!("    let {name} = r.read();\n");
+                args.push(name.to_owned());
+            }
+            ParamType::Ref { target, mutable } => {
+                let (binding, borrow) = if *mutable {
+                    mutated.get_or_insert(name.to_owned());
+                    ("let mut", "&mut ")
+                } else {
+                    ("let", "&")
+                };
+                ret += &format!("    {binding} {name}: {target} = r.read();\n");
+                args.push(format!("{borrow}{name}"));
+            }
+        }
+    }
+    let call = format!("Solution::{}({})", method.name, args.join(", "));
+    match mutated {
+        Some(name) if !method.has_output => {
+            ret += &format!("    {call};\n    {name}\n");
+        }
+        _ => {
+            ret += &format!("    {call}\n");
+        }
+    }
+    ret
+}
+
+fn with_design_input_call(class: &str, methods: &[Method]) -> Result<String, String> {
+    let ctor = methods
+        .iter()
+        .find(|method| method.name == "new")
+        .ok_or(format!("impl {class} should contain new"))?;
+    let mut ret = format!(
+        "    with_design_input! {{ r => {class}::new({});\n",
+        param_names(ctor).join(", ")
+    );
+    for method in methods.iter().filter(|method| method.name != "new") {
+        let has_refs = method
+            .params
+            .iter()
+            .any(|param| matches!(param.ty, ParamType::Ref { .. }));
+        if has_refs {
+            return Err(format!(
+                "{}: reference parameters are not supported in design problems",
+                method.name
+            ));
+        }
+        ret += &format!(
+            "        {}({});\n",
+            method.name,
+            param_names(method).join(", ")
+        );
+    }
+    ret += "    }\n";
+    Ok(ret)
+}
+
+fn param_names(method: &Method) -> Vec<String> {
+    method
+        .params
+        .iter()
+        .map(|param| param.name.to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate_solution;
+
+    #[test]
+    pub fn generate_with_input() {
+        let snippet = "
+impl Solution {
+    pub fn two_sum(nums: Vec<i32>, target: i32) -> Vec<i32> {
+
+    }
+}";
+        check_exec(snippet, "    with_input!{ r => two_sum(nums, target) }\n");
+    }
+
+    #[test]
+    pub fn generate_mutated_param() {
+        let snippet = "
+impl Solution {
+    pub fn rotate(nums: &mut Vec<i32>, k: i32) {
+
+    }
+}";
+        check_exec(
+            snippet,
+            "    let mut nums: Vec<i32> = r.read();
+    let k = r.read();
+    Solution::rotate(&mut nums, k);
+    nums
+",
+        );
+    }
+
+    #[test]
+    pub fn generate_design() {
+        let snippet = "
+struct MedianFinder {
+
+}
+
+impl MedianFinder {
+
+    fn new() -> Self {
+
+    }
+
+    fn add_num(&mut self, num: i32) {
+
+    }
+
+    fn find_median(&self) -> i64 {
+
+    }
+}";
+        check_exec(
+            snippet,
+            "    with_design_input! { r => MedianFinder::new();
+        add_num(num);
+        find_median();
+    }
+",
+        );
+    }
+
+    #[test]
+    pub fn generate_design_with_usage_comment() {
+        let snippet = "struct MinStack {
+
+}
+
+
+/**
+ * `&self` means the method has a read-only receiver.
+ * `&mut self` means the method has a mutable receiver.
+ */
+impl MinStack {
+
+    fn new() -> Self {
+        
+    }
+    
+    fn push(&mut self, val: i32) {
+        
+    }
+    
+    fn pop(&mut self) {
+        
+    }
+    
+    fn top(&self) -> i32 {
+        
+    }
+    
+    fn get_min(&self) -> i32 {
+        
+    }
+}
+
+/**
+ * Your MinStack object will be instantiated and called as such:
+ * let obj = MinStack::new();
+ * obj.push(val);
+ * obj.pop();
+ * let ret_3: i32 = obj.top();
+ * let ret_4: i32 = obj.get_min();
+ */
+";
+        check_exec(
+            snippet,
+            "    with_design_input! { r => MinStack::new();
+        push(val);
+        pop();
+        top();
+        get_min();
+    }
+",
+        );
+        let generated = generate_solution(snippet).unwrap();
+        assert!(!generated.contains("will be instantiated"));
+    }
+
+    #[test]
+    pub fn generate_invalid() {
+        let err = generate_solution("impl Solution { pub fn f( }").unwrap_err();
+        assert!(err.starts_with("Unable to parse snippet"), "{err}");
+    }
+
+    #[test]
+    pub fn generate_unsupported() {
+        for (snippet, expected) in [
+            (
+                "impl Solution { pub fn my_pow(x: f64, n: i32) -> f64 {} }",
+                "my_pow: type f64 is not supported",
+            ),
+            (
+                "impl Solution { pub fn f((a, b): (i32, i32)) {} }",
+                "f: unsupported parameter pattern",
+            ),
+            ("impl Solution {}", "impl Solution should contain a method"),
+            (
+                "struct Counter {} impl Counter { fn inc(&mut self) {} }",
+                "impl Counter should contain new",
+            ),
+            (
+                "struct Counter {} impl Counter { fn new() -> Self {} fn add(&mut self, a: &Vec<i32>) {} }",
+                "add: reference parameters are not supported in design problems",
+            ),
+        ] {
+            let err = generate_solution(snippet).unwrap_err();
+            assert_eq!(err, expected, "{snippet}");
+        }
+    }
+
+    fn check_exec(snippet: &str, expected: &str) {
+        let actual = generate_solution(snippet).unwrap();
+        let expected = format!("ToLeetcodeValueNode {{\n{expected}}}\n");
+        assert!(
+            actual.contains(&expected),
+            "expected exec body:\n{expected}\ngenerated:\n{actual}"
+        );
+    }
+}
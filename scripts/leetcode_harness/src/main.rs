@@ -0,0 +1,24 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use generate::generate_solution;
+
+pub mod generate;
+pub mod signature;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if let [_, arg1, arg2] = &args[..] {
+        let snippet = fs::read_to_string(arg1).unwrap();
+        match generate_solution(&snippet) {
+            Ok(solution) => fs::write(arg2, solution).unwrap(),
+            Err(err) => {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        }
+    } else {
+        panic!("expected 2 args, got {:?}", &args[1..]);
+    }
+}
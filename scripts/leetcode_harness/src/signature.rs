@@ -0,0 +1,203 @@
+use syn::{FnArg, GenericArgument, ImplItem, Item, Pat, PathArguments, ReturnType, Type};
+
+pub struct Method {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub has_output: bool,
+}
+
+pub struct Param {
+    pub name: String,
+    pub ty: ParamType,
+}
+
+pub enum ParamType {
+    Value,
+    Ref { target: String, mutable: bool },
+}
+
+pub enum Snippet {
+    Solution(Method),
+    Design { class: String, methods: Vec<Method> },
+}
+
+pub fn parse_snippet(source_code: &str) -> Result<Snippet, String> {
+    let file = syn::parse_file(strip_trailing_comment(source_code))
+        .map_err(|err| format!("Unable to parse snippet: {err}"))?;
+    let item_impl = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Impl(item_impl) => Some(item_impl),
+            _ => None,
+        })
+        .ok_or("snippet should contain an impl block")?;
+    let class = type_to_string(&item_impl.self_ty);
+    let mut methods = item_impl
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Method(method) => Some(parse_method(&method.sig)),
+            _ => None,
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if class == "Solution" {
+        if methods.is_empty() {
+            return Err(String::from("impl Solution should contain a method"));
+        }
+        if methods.len() > 1 {
+            eprintln!("Multiple methods in impl Solution, using the first one");
+        }
+        Ok(Snippet::Solution(methods.swap_remove(0)))
+    } else {
+        Ok(Snippet::Design { class, methods })
+    }
+}
+
+/// Design snippets end with a usage example in a doc comment that isn't attached to any item,
+/// syn doesn't accept it
+pub fn strip_trailing_comment(source_code: &str) -> &str {
+    let source_code = source_code.trim_end();
+    match source_code
+        .strip_suffix("*/")
+        .and_then(|rest| rest.rfind("/*"))
+    {
+        Some(start) => source_code[..start].trim_end(),
+        None => source_code,
+    }
+}
+
+fn parse_method(sig: &syn::Signature) -> Result<Method, String> {
+    let name = sig.ident.to_string();
+    let params = sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Receiver(_) => None,
+            FnArg::Typed(pat_type) => Some(parse_param(&name, &pat_type.pat, &pat_type.ty)),
+        })
+        .collect::<Result<_, _>>()?;
+    let has_output = match &sig.output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, ty) => {
+            check_supported(&name, ty)?;
+            true
+        }
+    };
+    Ok(Method {
+        name,
+        params,
+        has_output,
+    })
+}
+
+fn parse_param(method: &str, pat: &Pat, ty: &Type) -> Result<Param, String> {
+    let name = match pat {
+        Pat::Ident(pat_ident) => pat_ident.ident.to_string(),
+        _ => return Err(format!("{method}: unsupported parameter pattern")),
+    };
+    let ty = match ty {
+        Type::Reference(reference) => {
+            let target = match type_to_string(&reference.elem).as_str() {
+                "str" => String::from("String"),
+                _ => {
+                    check_supported(method, &reference.elem)?;
+                    type_to_string(&reference.elem)
+                }
+            };
+            ParamType::Ref {
+                target,
+                mutable: reference.mutability.is_some(),
+            }
+        }
+        other => {
+            check_supported(method, other)?;
+            ParamType::Value
+        }
+    };
+    Ok(Param { name, ty })
+}
+
+/// Types that have `FromLeetcodeValueNode` and `ToLeetcodeValueNode` implementations
+fn check_supported(method: &str, ty: &Type) -> Result<(), String> {
+    if is_supported(ty) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{method}: type {} is not supported",
+            type_to_string(ty)
+        ))
+    }
+}
+
+fn is_supported(ty: &Type) -> bool {
+    if matches!(
+        type_to_string(ty).as_str(),
+        "Self" | "Option<Rc<RefCell<TreeNode>>>"
+    ) {
+        return true;
+    }
+    match generic_type(ty) {
+        Some((name, None)) => matches!(
+            name.as_str(),
+            "i32" | "i64" | "u32" | "bool" | "char" | "String"
+        ),
+        Some((name, Some(inner))) => {
+            matches!(name.as_str(), "Vec" | "Option") && is_supported(inner)
+        }
+        None => false,
+    }
+}
+
+/// Splits `Name<Inner>` into the last path segment name and its single type argument
+fn generic_type(ty: &Type) -> Option<(String, Option<&Type>)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let name = segment.ident.to_string();
+    match &segment.arguments {
+        PathArguments::None => Some((name, None)),
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some((name, Some(inner))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn type_to_string(ty: &Type) -> String {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .iter()
+            .map(|segment| {
+                let mut s = segment.ident.to_string();
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    let args: Vec<String> = args
+                        .args
+                        .iter()
+                        .map(|arg| match arg {
+                            GenericArgument::Type(ty) => type_to_string(ty),
+                            _ => String::from("_"),
+                        })
+                        .collect();
+                    s += &format!("<{}>", args.join(", "));
+                }
+                s
+            })
+            .collect::<Vec<_>>()
+            .join("::"),
+        Type::Reference(reference) => {
+            let mutability = if reference.mutability.is_some() {
+                "mut "
+            } else {
+                ""
+            };
+            format!("&{mutability}{}", type_to_string(&reference.elem))
+        }
+        Type::Tuple(tuple) if tuple.elems.is_empty() => String::from("()"),
+        _ => String::from("_"),
+    }
+}
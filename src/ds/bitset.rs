@@ -1,5 +1,7 @@
 use std::fmt::{Debug, Write};
-use std::ops::{BitOr, BitOrAssign, ShlAssign};
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, ShlAssign, ShrAssign,
+};
 
 use crate::math::utils::div_up;
 
@@ -48,10 +50,78 @@ impl Bitset {
         (0..self.n).map(|i| self.get(i))
     }
 
+    /// Indices of the set bits in increasing order
+    pub fn ones<'a>(&'a self) -> impl Iterator<Item = usize> + 'a {
+        std::iter::successors(self.find_first(), move |&i| self.find_next(i))
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(|block| block.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn any(&self) -> bool {
+        self.blocks.iter().any(|&block| block != BLOCK_ALL_0)
+    }
+
+    pub fn none(&self) -> bool {
+        !self.any()
+    }
+
+    pub fn find_first(&self) -> Option<usize> {
+        self.find_from_block(0)
+    }
+
+    /// First set bit after `i`
+    pub fn find_next(&self, i: usize) -> Option<usize> {
+        let i = i + 1;
+        if i >= self.n {
+            return None;
+        }
+        let block = self.block(i) & !(Self::mask(i) - 1);
+        if block != BLOCK_ALL_0 {
+            Some(i / BLOCK_SZ * BLOCK_SZ + block.trailing_zeros() as usize)
+        } else {
+            self.find_from_block(i / BLOCK_SZ + 1)
+        }
+    }
+
+    /// New bits are 0
+    pub fn resize(&mut self, n: usize) {
+        self.n = n;
+        self.blocks.resize(div_up(n, BLOCK_SZ), BLOCK_ALL_0);
+        self.apply_last_block_mask();
+    }
+
     pub fn shl_or(&mut self, d: usize) {
         self.shl_op(d, |old, new| new.bitor(old));
     }
 
+    pub fn shr_or(&mut self, d: usize) {
+        self.shr_op(d, |old, new| new.bitor(old));
+    }
+
+    fn shr_op(&mut self, d: usize, op: fn(usize, usize) -> usize) {
+        let shr_l = (d % BLOCK_SZ) as u32;
+        let shl_r = BLOCK_SZ as u32 - shr_l;
+        for i in 0..self.blocks.len() {
+            let j = i + d / BLOCK_SZ;
+            let block_l = self.blocks.get(j).copied().unwrap_or(BLOCK_ALL_0);
+            let block_r = self.blocks.get(j + 1).copied().unwrap_or(BLOCK_ALL_0);
+            let upd = (block_l >> shr_l) | block_r.checked_shl(shl_r).unwrap_or(BLOCK_ALL_0);
+            let block = &mut self.blocks[i];
+            *block = op(*block, upd);
+        }
+    }
+
+    fn find_from_block(&self, start: usize) -> Option<usize> {
+        (start..self.blocks.len())
+            .find(|&j| self.blocks[j] != BLOCK_ALL_0)
+            .map(|j| j * BLOCK_SZ + self.blocks[j].trailing_zeros() as usize)
+    }
+
     fn shl_op(&mut self, d: usize, op: fn(usize, usize) -> usize) {
         let r = d % BLOCK_SZ;
         let mask_l = (1 << r) - 1;
@@ -104,12 +174,79 @@ impl BitOrAssign<&Self> for Bitset {
     }
 }
 
+impl BitAndAssign<&Self> for Bitset {
+    fn bitand_assign(&mut self, rhs: &Self) {
+        assert_eq!(self.len(), rhs.len());
+        for (block, other_block) in self.blocks.iter_mut().zip(rhs.blocks.iter()) {
+            *block &= other_block
+        }
+    }
+}
+
+impl BitXorAssign<&Self> for Bitset {
+    fn bitxor_assign(&mut self, rhs: &Self) {
+        assert_eq!(self.len(), rhs.len());
+        for (block, other_block) in self.blocks.iter_mut().zip(rhs.blocks.iter()) {
+            *block ^= other_block
+        }
+    }
+}
+
+impl BitOr for &Bitset {
+    type Output = Bitset;
+
+    fn bitor(self, rhs: Self) -> Bitset {
+        let mut res = self.clone();
+        res |= rhs;
+        res
+    }
+}
+
+impl BitAnd for &Bitset {
+    type Output = Bitset;
+
+    fn bitand(self, rhs: Self) -> Bitset {
+        let mut res = self.clone();
+        res &= rhs;
+        res
+    }
+}
+
+impl BitXor for &Bitset {
+    type Output = Bitset;
+
+    fn bitxor(self, rhs: Self) -> Bitset {
+        let mut res = self.clone();
+        res ^= rhs;
+        res
+    }
+}
+
+impl Not for &Bitset {
+    type Output = Bitset;
+
+    fn not(self) -> Bitset {
+        let mut res = self.clone();
+        for block in res.blocks.iter_mut() {
+            *block = !*block;
+        }
+        res.apply_last_block_mask();
+        res
+    }
+}
+
 impl ShlAssign<usize> for Bitset {
     fn shl_assign(&mut self, d: usize) {
         self.shl_op(d, |_old, new| new);
     }
 }
 
+impl ShrAssign<usize> for Bitset {
+    fn shr_assign(&mut self, d: usize) {
+        self.shr_op(d, |_old, new| new);
+    }
+}
+
 impl Debug for Bitset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for bt in self.bits() {
@@ -1,11 +1,18 @@
+use std::ops::{Add, Neg};
+
 pub struct Dsu {
     p: Vec<usize>,
+    /// Valid for the roots only
+    size: Vec<usize>,
+    components: usize,
 }
 
 impl Dsu {
     pub fn new(n: usize) -> Self {
         Self {
             p: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
         }
     }
 
@@ -17,11 +24,212 @@ impl Dsu {
     }
 
     pub fn join(&mut self, v: usize, u: usize) -> bool {
-        let pv = self.parent(v);
-        let pu = self.parent(u);
+        let mut pv = self.parent(v);
+        let mut pu = self.parent(u);
+        if pv != pu {
+            if self.size[pv] > self.size[pu] {
+                std::mem::swap(&mut pv, &mut pu);
+            }
+            self.p[pv] = pu;
+            self.size[pu] += self.size[pv];
+            self.components -= 1;
+        }
+        pv != pu
+    }
+
+    /// Size of the component of `v`
+    pub fn size(&mut self, v: usize) -> usize {
+        let p = self.parent(v);
+        self.size[p]
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Vertices of each component, ordered by the smallest vertex
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.p.len();
+        let mut index = vec![usize::MAX; n];
+        let mut res: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for v in 0..n {
+            let p = self.parent(v);
+            if index[p] == usize::MAX {
+                index[p] = res.len();
+                res.push(Vec::with_capacity(self.size[p]));
+            }
+            res[index[p]].push(v);
+        }
+        res
+    }
+}
+
+/// `Dsu` without path compression, so that joins can be undone in reverse order:
+/// `O(log n)` per operation
+pub struct RollbackDsu {
+    p: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+    /// Roots attached by the successful joins
+    history: Vec<usize>,
+}
+
+impl RollbackDsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            p: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn parent(&self, mut v: usize) -> usize {
+        while self.p[v] != v {
+            v = self.p[v];
+        }
+        v
+    }
+
+    pub fn join(&mut self, v: usize, u: usize) -> bool {
+        let mut pv = self.parent(v);
+        let mut pu = self.parent(u);
         if pv != pu {
+            if self.size[pv] > self.size[pu] {
+                std::mem::swap(&mut pv, &mut pu);
+            }
             self.p[pv] = pu;
+            self.size[pu] += self.size[pv];
+            self.components -= 1;
+            self.history.push(pv);
         }
         pv != pu
     }
+
+    pub fn size(&self, v: usize) -> usize {
+        self.size[self.parent(v)]
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// State to return to with `rollback`
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes the joins made after `snapshot` was taken
+    pub fn rollback(&mut self, snapshot: usize) {
+        assert!(snapshot <= self.history.len());
+        while self.history.len() > snapshot {
+            let v = self.history.pop().unwrap();
+            let p = self.p[v];
+            self.size[p] -= self.size[v];
+            self.p[v] = v;
+            self.components += 1;
+        }
+    }
+}
+
+/// Abelian group of the values in `PotentialDsu`
+pub trait Potential: Copy + PartialEq {
+    fn op(l: Self, r: Self) -> Self;
+
+    /// op(x, inv(x)) = e()
+    fn inv(x: Self) -> Self;
+
+    fn e() -> Self;
+}
+
+/// Signed numbers and `Modulo` under addition
+impl<T> Potential for T
+where
+    T: Copy + PartialEq + Default + Add<Output = T> + Neg<Output = T>,
+{
+    fn op(l: Self, r: Self) -> Self {
+        l + r
+    }
+
+    fn inv(x: Self) -> Self {
+        -x
+    }
+
+    fn e() -> Self {
+        T::default()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Xor(pub u64);
+
+impl Potential for Xor {
+    fn op(l: Self, r: Self) -> Self {
+        Self(l.0 ^ r.0)
+    }
+
+    fn inv(x: Self) -> Self {
+        x
+    }
+
+    fn e() -> Self {
+        Self(0)
+    }
+}
+
+/// `Dsu` that keeps the difference `x_v - x_u` of any two connected vertices
+pub struct PotentialDsu<W> {
+    p: Vec<usize>,
+    size: Vec<usize>,
+    /// `x_v - x_p[v]`
+    pot: Vec<W>,
+}
+
+impl<W: Potential> PotentialDsu<W> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            p: (0..n).collect(),
+            size: vec![1; n],
+            pot: vec![W::e(); n],
+        }
+    }
+
+    pub fn parent(&mut self, v: usize) -> usize {
+        let p = self.p[v];
+        if p != v {
+            self.p[v] = self.parent(p);
+            self.pot[v] = W::op(self.pot[v], self.pot[p]);
+        }
+        self.p[v]
+    }
+
+    /// Adds the constraint `x_v - x_u = w`,
+    /// returns `false` if it contradicts the previous ones (the constraint is dropped then)
+    pub fn join(&mut self, v: usize, u: usize, w: W) -> bool {
+        let (pv, pu) = (self.parent(v), self.parent(u));
+        if pv == pu {
+            return self.diff(v, u) == Some(w);
+        }
+        // x_pv - x_pu
+        let w = W::op(W::op(w, W::inv(self.pot[v])), self.pot[u]);
+        if self.size[pv] <= self.size[pu] {
+            self.p[pv] = pu;
+            self.pot[pv] = w;
+            self.size[pu] += self.size[pv];
+        } else {
+            self.p[pu] = pv;
+            self.pot[pu] = W::inv(w);
+            self.size[pv] += self.size[pu];
+        }
+        true
+    }
+
+    /// `x_v - x_u` if `v` and `u` are connected
+    pub fn diff(&mut self, v: usize, u: usize) -> Option<W> {
+        if self.parent(v) == self.parent(u) {
+            Some(W::op(self.pot[v], W::inv(self.pot[u])))
+        } else {
+            None
+        }
+    }
 }
@@ -0,0 +1,241 @@
+use std::ops::{Bound, Range, RangeBounds};
+
+use super::lazy_seg_tree::LazySegTreeUpdate;
+use super::seg_tree::SegTreeValue;
+
+/// Update type of a `DynamicSegTree` without range updates
+#[derive(Copy, Clone)]
+pub struct NoUpdate;
+
+impl<V: SegTreeValue> LazySegTreeUpdate<V> for NoUpdate {
+    fn apply(_upd: &Self, val: &V, _len: usize) -> V {
+        val.clone()
+    }
+
+    fn combine(_upd: &Self, _other: &Self) -> Self {
+        NoUpdate
+    }
+
+    fn id() -> Self {
+        NoUpdate
+    }
+}
+
+struct Node<V, U> {
+    /// Doesn't include `upd`
+    val: V,
+    upd: U,
+    /// 0 if the children aren't created yet: all their values are `e()`
+    left: usize,
+    right: usize,
+}
+
+/// Segment tree over `i64` positions where nodes are created on `set`/`apply` only,
+/// so the range may be as large as `i64` allows without coordinate compression.
+///
+/// # Example
+/// ```
+/// seg_tree_value_sum!(Sum, i64);
+/// seg_tree_update_add_sum!(Add, Sum, i64);
+/// let mut st = DynamicSegTree::<Sum>::with_range(0..1_000_000_000_000_000_000);
+/// st.set(123_456_789_000, Sum(5));
+/// assert_eq!(st.get(0..1_000_000_000_000).0, 5);
+/// let mut st = DynamicSegTree::<Sum, Add>::with_range(-1_000_000_000..1_000_000_000);
+/// st.apply(-10..10, Add(1));
+/// assert_eq!(st.get(0..100).0, 10);
+/// ```
+pub struct DynamicSegTree<V, U = NoUpdate> {
+    range: Range<i64>,
+    /// The root is node 0
+    nodes: Vec<Node<V, U>>,
+}
+
+impl<V: SegTreeValue, U: LazySegTreeUpdate<V>> DynamicSegTree<V, U> {
+    /// Positions are `range.start..range.end`, so `i64::MAX` is never a position:
+    /// the whole key space is `i64::MIN..i64::MAX`, queried with `..`
+    pub fn with_range(range: Range<i64>) -> Self {
+        assert!(
+            range.start < range.end,
+            "{range:?}: empty range is not supported"
+        );
+        let mut ret = Self {
+            range,
+            nodes: Vec::new(),
+        };
+        ret.add_node();
+        ret
+    }
+
+    /// Number of created nodes, grows by `O(log range)` with each modification
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn get(&self, rng_bounds: impl RangeBounds<i64>) -> V {
+        let rng = self.unpack_range(rng_bounds);
+        if rng.is_empty() {
+            V::e()
+        } else {
+            self.calc(0, self.range.clone(), &rng)
+        }
+    }
+
+    pub fn set(&mut self, i: i64, val: V) {
+        assert!(self.range.contains(&i), "{i} is out of {:?}", self.range);
+        self.update_point(0, self.range.clone(), i, val);
+    }
+
+    pub fn apply(&mut self, rng_bounds: impl RangeBounds<i64>, upd: U) {
+        let rng = self.unpack_range(rng_bounds);
+        if !rng.is_empty() {
+            self.update(0, self.range.clone(), &rng, &upd);
+        }
+    }
+
+    /// A bound that overflows `i64` is out of `range` anyway
+    fn unpack_range(&self, rng_bounds: impl RangeBounds<i64>) -> Range<i64> {
+        let start = match rng_bounds.start_bound() {
+            Bound::Included(&l) => Some(l),
+            Bound::Excluded(&l) => l.checked_add(1),
+            Bound::Unbounded => Some(self.range.start),
+        };
+        let end = match rng_bounds.end_bound() {
+            Bound::Included(&r) => r.checked_add(1),
+            Bound::Excluded(&r) => Some(r),
+            Bound::Unbounded => Some(self.range.end),
+        };
+        match (start, end) {
+            (Some(start), Some(end))
+                if start <= end && self.range.start <= start && end <= self.range.end =>
+            {
+                start..end
+            }
+            _ => panic!(
+                "{:?}: range does not meet {:?} boundary",
+                (rng_bounds.start_bound(), rng_bounds.end_bound()),
+                self.range
+            ),
+        }
+    }
+
+    /// Value of the intersection of `range` and `rng`, computed without creating nodes:
+    /// the update of a node is applied to the part of its segment
+    fn calc(&self, node: usize, range: Range<i64>, rng: &Range<i64>) -> V {
+        let Node {
+            val,
+            upd,
+            left,
+            right,
+        } = &self.nodes[node];
+        if rng.start <= range.start && range.end <= rng.end {
+            return U::apply(upd, val, seg_len(&range));
+        }
+        if *left == 0 {
+            let start = std::cmp::max(range.start, rng.start);
+            let end = std::cmp::min(range.end, rng.end);
+            return U::apply(upd, &V::e(), seg_len(&(start..end)));
+        }
+        let mid = seg_mid(&range);
+        let res = if rng.end <= mid {
+            self.calc(*left, range.start..mid, rng)
+        } else if mid <= rng.start {
+            self.calc(*right, mid..range.end, rng)
+        } else {
+            V::op(
+                &self.calc(*left, range.start..mid, rng),
+                &self.calc(*right, mid..range.end, rng),
+            )
+        };
+        let start = std::cmp::max(range.start, rng.start);
+        let end = std::cmp::min(range.end, rng.end);
+        U::apply(upd, &res, seg_len(&(start..end)))
+    }
+
+    fn update_point(&mut self, node: usize, range: Range<i64>, i: i64, val: V) {
+        if seg_len(&range) == 1 {
+            self.nodes[node].val = val;
+            self.nodes[node].upd = U::id();
+            return;
+        }
+        let (left, right) = self.push_down(node, &range);
+        let mid = seg_mid(&range);
+        if i < mid {
+            self.update_point(left, range.start..mid, i, val);
+        } else {
+            self.update_point(right, mid..range.end, i, val);
+        }
+        self.recalc(node, &range);
+    }
+
+    fn update(&mut self, node: usize, range: Range<i64>, rng: &Range<i64>, upd: &U) {
+        if rng.start <= range.start && range.end <= rng.end {
+            let cur = &mut self.nodes[node].upd;
+            *cur = U::combine(cur, upd);
+            return;
+        }
+        let (left, right) = self.push_down(node, &range);
+        let mid = seg_mid(&range);
+        if rng.start < mid {
+            self.update(left, range.start..mid, rng, upd);
+        }
+        if mid < rng.end {
+            self.update(right, mid..range.end, rng, upd);
+        }
+        self.recalc(node, &range);
+    }
+
+    /// Creates the children if needed and passes the update down to them
+    fn push_down(&mut self, node: usize, range: &Range<i64>) -> (usize, usize) {
+        if self.nodes[node].left == 0 {
+            let left = self.add_node();
+            let right = self.add_node();
+            self.nodes[node].left = left;
+            self.nodes[node].right = right;
+        }
+        let Node {
+            val,
+            upd,
+            left,
+            right,
+        } = &mut self.nodes[node];
+        *val = U::apply(upd, val, seg_len(range));
+        let upd = std::mem::replace(upd, U::id());
+        let (left, right) = (*left, *right);
+        for child in [left, right] {
+            let cur = &mut self.nodes[child].upd;
+            *cur = U::combine(cur, &upd);
+        }
+        (left, right)
+    }
+
+    fn recalc(&mut self, node: usize, range: &Range<i64>) {
+        let mid = seg_mid(range);
+        let Node { left, right, .. } = self.nodes[node];
+        let left_val = self.at(left, &(range.start..mid));
+        let right_val = self.at(right, &(mid..range.end));
+        self.nodes[node].val = V::op(&left_val, &right_val);
+    }
+
+    fn at(&self, node: usize, range: &Range<i64>) -> V {
+        let Node { val, upd, .. } = &self.nodes[node];
+        U::apply(upd, val, seg_len(range))
+    }
+
+    fn add_node(&mut self) -> usize {
+        self.nodes.push(Node {
+            val: V::e(),
+            upd: U::id(),
+            left: 0,
+            right: 0,
+        });
+        self.nodes.len() - 1
+    }
+}
+
+fn seg_len(range: &Range<i64>) -> usize {
+    range.end.wrapping_sub(range.start) as u64 as usize
+}
+
+fn seg_mid(range: &Range<i64>) -> i64 {
+    range.start + (seg_len(range) / 2) as i64
+}
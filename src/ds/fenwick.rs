@@ -0,0 +1,217 @@
+use std::ops::{Add, Mul, RangeBounds, Sub};
+
+use crate::utils::misc::{assert_range, unpack_range};
+
+/// Element of an abelian group, `Default` is the zero: `i64`, `Modulo`, ...
+pub trait FenwickValue: Copy + Default + Add<Output = Self> + Sub<Output = Self> {}
+
+impl<T> FenwickValue for T where T: Copy + Default + Add<Output = T> + Sub<Output = T> {}
+
+/// Point add, range sum
+pub struct Fenwick<T> {
+    /// 1-based, `tree[i]` is the sum of `(i - lowbit(i))..i`
+    tree: Vec<T>,
+}
+
+impl<T: FenwickValue> Fenwick<T> {
+    pub fn with_len(n: usize) -> Self {
+        Self {
+            tree: vec![T::default(); n + 1],
+        }
+    }
+
+    pub fn new(a: &[T]) -> Self {
+        let mut tree = vec![T::default(); a.len() + 1];
+        tree[1..].copy_from_slice(a);
+        for i in 1..tree.len() {
+            let parent = i + lowbit(i);
+            if parent < tree.len() {
+                tree[parent] = tree[parent] + tree[i];
+            }
+        }
+        Self { tree }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn add(&mut self, i: usize, val: T) {
+        assert!(i < self.len());
+        let mut i = i + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i] + val;
+            i += lowbit(i);
+        }
+    }
+
+    /// Sum of `0..end`
+    pub fn prefix(&self, end: usize) -> T {
+        assert!(end <= self.len());
+        let mut res = T::default();
+        let mut i = end;
+        while i > 0 {
+            res = res + self.tree[i];
+            i -= lowbit(i);
+        }
+        res
+    }
+
+    pub fn get(&self, rng_bounds: impl RangeBounds<usize>) -> T {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.len(), true);
+        self.prefix(rng.end) - self.prefix(rng.start)
+    }
+}
+
+impl<T: FenwickValue + Ord> Fenwick<T> {
+    /// Min `i` such that the sum of `0..=i` is at least `k`, `len()` if there is no such `i`.
+    /// All the values should be non-negative, e.g. counts for the k-th order statistic.
+    pub fn lower_bound(&self, k: T) -> usize {
+        let mut pos = 0;
+        let mut acc = T::default();
+        let mut step = self.len().next_power_of_two();
+        while step > 0 {
+            if pos + step <= self.len() && acc + self.tree[pos + step] < k {
+                pos += step;
+                acc = acc + self.tree[pos];
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
+/// Range add, range sum on top of two `Fenwick`s
+pub struct RangeFenwick<T> {
+    /// Added values
+    add: Fenwick<T>,
+    /// Added values multiplied by their positions
+    weighted: Fenwick<T>,
+}
+
+impl<T> RangeFenwick<T>
+where
+    T: FenwickValue + Mul<Output = T> + TryFrom<usize>,
+{
+    pub fn with_len(n: usize) -> Self {
+        Self {
+            add: Fenwick::with_len(n + 1),
+            weighted: Fenwick::with_len(n + 1),
+        }
+    }
+
+    pub fn new(a: &[T]) -> Self {
+        let mut diff = vec![T::default(); a.len() + 1];
+        let mut weighted = vec![T::default(); a.len() + 1];
+        for i in 0..a.len() {
+            diff[i] = diff[i] + a[i];
+            diff[i + 1] = diff[i + 1] - a[i];
+            weighted[i] = weighted[i] + a[i] * Self::index(i);
+            weighted[i + 1] = weighted[i + 1] - a[i] * Self::index(i + 1);
+        }
+        Self {
+            add: Fenwick::new(&diff),
+            weighted: Fenwick::new(&weighted),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.add.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn add(&mut self, rng_bounds: impl RangeBounds<usize>, val: T) {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.len(), true);
+        let neg = T::default() - val;
+        self.add.add(rng.start, val);
+        self.add.add(rng.end, neg);
+        self.weighted.add(rng.start, val * Self::index(rng.start));
+        self.weighted.add(rng.end, neg * Self::index(rng.end));
+    }
+
+    pub fn get(&self, rng_bounds: impl RangeBounds<usize>) -> T {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.len(), true);
+        self.prefix(rng.end) - self.prefix(rng.start)
+    }
+
+    fn prefix(&self, end: usize) -> T {
+        self.add.prefix(end) * Self::index(end) - self.weighted.prefix(end)
+    }
+
+    fn index(i: usize) -> T {
+        T::try_from(i).unwrap_or_else(|_| panic!("{i} doesn't fit into the value type"))
+    }
+}
+
+/// Point add, rectangle sum
+pub struct Fenwick2D<T> {
+    n: usize,
+    m: usize,
+    /// 1-based in both dimensions
+    tree: Vec<Vec<T>>,
+}
+
+impl<T: FenwickValue> Fenwick2D<T> {
+    pub fn with_size(n: usize, m: usize) -> Self {
+        Self {
+            n,
+            m,
+            tree: vec![vec![T::default(); m + 1]; n + 1],
+        }
+    }
+
+    pub fn add(&mut self, i: usize, j: usize, val: T) {
+        assert!(i < self.n && j < self.m);
+        let mut i = i + 1;
+        while i <= self.n {
+            let mut j = j + 1;
+            while j <= self.m {
+                self.tree[i][j] = self.tree[i][j] + val;
+                j += lowbit(j);
+            }
+            i += lowbit(i);
+        }
+    }
+
+    /// Sum of the rectangle `0..rows_end` x `0..cols_end`
+    pub fn prefix(&self, rows_end: usize, cols_end: usize) -> T {
+        assert!(rows_end <= self.n && cols_end <= self.m);
+        let mut res = T::default();
+        let mut i = rows_end;
+        while i > 0 {
+            let mut j = cols_end;
+            while j > 0 {
+                res = res + self.tree[i][j];
+                j -= lowbit(j);
+            }
+            i -= lowbit(i);
+        }
+        res
+    }
+
+    pub fn get(&self, rows: impl RangeBounds<usize>, cols: impl RangeBounds<usize>) -> T {
+        let rows = unpack_range(rows);
+        let cols = unpack_range(cols);
+        assert_range(&rows, 0..self.n, true);
+        assert_range(&cols, 0..self.m, true);
+        self.prefix(rows.end, cols.end)
+            - self.prefix(rows.start, cols.end)
+            - self.prefix(rows.end, cols.start)
+            + self.prefix(rows.start, cols.start)
+    }
+}
+
+#[inline]
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
@@ -0,0 +1,190 @@
+use std::ops::RangeBounds;
+
+use crate::utils::misc::{assert_range, unpack_range};
+
+use super::seg_tree::SegTreeValue;
+
+/// Non-recursive version of `SegTree` with the same API,
+/// nodes are stored bottom-up like in AtCoder library:
+/// https://atcoder.github.io/ac-library/production/document_en/segtree.html
+///
+/// Benchmark: `iterative_seg_tree_bench` test
+pub struct IterativeSegTree<T> {
+    n: usize,
+    size: usize,
+    log: u32,
+    st: Vec<T>,
+}
+
+impl<T: SegTreeValue> IterativeSegTree<T> {
+    pub fn with_len(n: usize) -> Self {
+        let size = n.next_power_of_two();
+        Self {
+            n,
+            size,
+            log: size.trailing_zeros(),
+            st: vec![T::e(); 2 * size],
+        }
+    }
+
+    pub fn new(a: &[T]) -> Self {
+        let mut ret = Self::with_len(a.len());
+        ret.st[ret.size..ret.size + a.len()].clone_from_slice(a);
+        for i in (1..ret.size).rev() {
+            ret.recalc(i);
+        }
+        ret
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn get(&self, rng_bounds: impl RangeBounds<usize>) -> T {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.n, true);
+        let (mut l, mut r) = (rng.start + self.size, rng.end + self.size);
+        let (mut left, mut right) = (T::e(), T::e());
+        while l < r {
+            if l & 1 == 1 {
+                left = T::op(&left, &self.st[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right = T::op(&self.st[r], &right);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        T::op(&left, &right)
+    }
+
+    pub fn set(&mut self, i: usize, val: T) {
+        assert!(i < self.n);
+        let i = i + self.size;
+        self.st[i] = val;
+        for k in 1..=self.log {
+            self.recalc(i >> k);
+        }
+    }
+
+    /// [1 1 1 1 0 0]
+    ///  l     ^   r
+    pub fn max_prefix<P>(
+        &self,
+        rng_bounds: impl RangeBounds<usize>,
+        mut predicate: P,
+    ) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.n, true);
+        if rng.is_empty() {
+            return None;
+        }
+        let end = std::cmp::min(self.find_max_prefix_end(rng.start, &mut predicate), rng.end);
+        if end > rng.start {
+            Some(end - 1)
+        } else {
+            None
+        }
+    }
+
+    /// [0 0 1 1 1 1]
+    ///  l   ^     r
+    pub fn min_suffix<P>(
+        &self,
+        rng_bounds: impl RangeBounds<usize>,
+        mut predicate: P,
+    ) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.n, true);
+        if rng.is_empty() {
+            return None;
+        }
+        let start = std::cmp::max(
+            self.find_min_suffix_start(rng.end, &mut predicate),
+            rng.start,
+        );
+        if start < rng.end {
+            Some(start)
+        } else {
+            None
+        }
+    }
+
+    /// Max `r` such that `predicate(op(a[l..r]))` holds, `l` if it doesn't hold for `a[l]`
+    fn find_max_prefix_end<P>(&self, l: usize, predicate: &mut P) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut i = l + self.size;
+        let mut acc = T::e();
+        loop {
+            while i & 1 == 0 {
+                i >>= 1;
+            }
+            let next = T::op(&acc, &self.st[i]);
+            if !predicate(&next) {
+                while i < self.size {
+                    i *= 2;
+                    let next = T::op(&acc, &self.st[i]);
+                    if predicate(&next) {
+                        acc = next;
+                        i += 1;
+                    }
+                }
+                return i - self.size;
+            }
+            acc = next;
+            i += 1;
+            if i.is_power_of_two() {
+                return self.n;
+            }
+        }
+    }
+
+    /// Min `l` such that `predicate(op(a[l..r]))` holds, `r` if it doesn't hold for `a[r - 1]`
+    fn find_min_suffix_start<P>(&self, r: usize, predicate: &mut P) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut i = r + self.size;
+        let mut acc = T::e();
+        loop {
+            i -= 1;
+            while i > 1 && i & 1 == 1 {
+                i >>= 1;
+            }
+            let next = T::op(&self.st[i], &acc);
+            if !predicate(&next) {
+                while i < self.size {
+                    i = 2 * i + 1;
+                    let next = T::op(&self.st[i], &acc);
+                    if predicate(&next) {
+                        acc = next;
+                        i -= 1;
+                    }
+                }
+                return i + 1 - self.size;
+            }
+            acc = next;
+            if i.is_power_of_two() {
+                return 0;
+            }
+        }
+    }
+
+    fn recalc(&mut self, i: usize) {
+        self.st[i] = T::op(&self.st[2 * i], &self.st[2 * i + 1]);
+    }
+}
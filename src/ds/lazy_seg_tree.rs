@@ -3,11 +3,15 @@ use std::ops::{Range, RangeBounds};
 
 use crate::utils::misc::{assert_range, unpack_range};
 
-use super::seg_tree::{seg_tree_st_len, Pos, SegTreeValue};
+use super::seg_tree::{seg_tree_st_len, Pos, SearchOutput, SegTreeValue};
+
+pub trait LazySegTreeUpdate<V: SegTreeValue>: Clone {
+    /// `len` is the number of elements in the segment `val` is calculated for
+    fn apply(upd: &Self, val: &V, len: usize) -> V;
+
+    /// `upd` is applied first, `other` after it
+    fn combine(upd: &Self, other: &Self) -> Self;
 
-pub trait LazySegTreeUpdate<V: SegTreeValue>: Copy {
-    fn apply(upd: Self, val: V) -> V;
-    fn combine(upd: Self, other: Self) -> Self;
     fn id() -> Self;
 }
 
@@ -35,6 +39,14 @@ impl<V: SegTreeValue, U: LazySegTreeUpdate<V>> LazySegTree<V, U> {
         ret
     }
 
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
     pub fn get(&mut self, rng_bounds: impl RangeBounds<usize>) -> V {
         let rng = unpack_range(rng_bounds);
         assert_range(&rng, 0..self.n, true);
@@ -45,11 +57,63 @@ impl<V: SegTreeValue, U: LazySegTreeUpdate<V>> LazySegTree<V, U> {
         }
     }
 
+    pub fn get_point(&mut self, i: usize) -> V {
+        assert!(i < self.n);
+        self.calc(&(i..i + 1), &self.root())
+    }
+
+    pub fn set(&mut self, i: usize, val: V) {
+        assert!(i < self.n);
+        self.update_point(i, val, &self.root());
+    }
+
     pub fn apply(&mut self, rng_bounds: impl RangeBounds<usize>, upd: U) {
         let rng = unpack_range(rng_bounds);
         assert_range(&rng, 0..self.n, true);
         if !rng.is_empty() {
-            self.update(&rng, upd, &self.root());
+            self.update(&rng, &upd, &self.root());
+        }
+    }
+
+    /// [1 1 1 1 0 0]
+    ///  l     ^   r
+    pub fn max_prefix<P>(
+        &mut self,
+        rng_bounds: impl RangeBounds<usize>,
+        mut predicate: P,
+    ) -> Option<usize>
+    where
+        P: FnMut(&V) -> bool,
+    {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.n, true);
+        if rng.is_empty() {
+            return None;
+        }
+        match self.find_max_prefix(&rng, &mut predicate, &self.root(), V::e()) {
+            SearchOutput::All(_) => Some(rng.end - 1),
+            SearchOutput::Index(i) => i.checked_sub(1).filter(|&i| i >= rng.start),
+        }
+    }
+
+    /// [0 0 1 1 1 1]
+    ///  l   ^     r
+    pub fn min_suffix<P>(
+        &mut self,
+        rng_bounds: impl RangeBounds<usize>,
+        mut predicate: P,
+    ) -> Option<usize>
+    where
+        P: FnMut(&V) -> bool,
+    {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.n, true);
+        if rng.is_empty() {
+            return None;
+        }
+        match self.find_min_suffix(&rng, &mut predicate, &self.root(), V::e()) {
+            SearchOutput::All(_) => Some(rng.start),
+            SearchOutput::Index(i) => Some(i + 1).filter(|&i| i < rng.end),
         }
     }
 
@@ -64,16 +128,97 @@ impl<V: SegTreeValue, U: LazySegTreeUpdate<V>> LazySegTree<V, U> {
         } else if !left.intersects(range) {
             self.calc(range, right)
         } else {
-            V::op(self.calc(range, left), self.calc(range, right))
+            V::op(&self.calc(range, left), &self.calc(range, right))
+        }
+    }
+
+    fn find_max_prefix<P>(
+        &mut self,
+        rng: &Range<usize>,
+        predicate: &mut P,
+        pos: &Pos,
+        carryover: V,
+    ) -> SearchOutput<V>
+    where
+        P: FnMut(&V) -> bool,
+    {
+        if pos.is_inside(rng) {
+            let v = V::op(&carryover, &self.at(pos));
+            if (*predicate)(&v) {
+                return SearchOutput::All(v);
+            }
+        }
+        if let Some(i) = pos.single_point() {
+            return SearchOutput::Index(i);
+        }
+        let (ref left, ref right) = pos.split();
+        self.push_down(pos, left, right);
+        if left.intersects(rng) {
+            match self.find_max_prefix(rng, predicate, left, carryover) {
+                SearchOutput::All(v) if right.intersects(rng) => {
+                    self.find_max_prefix(rng, predicate, right, v)
+                }
+                output => output,
+            }
+        } else {
+            self.find_max_prefix(rng, predicate, right, carryover)
+        }
+    }
+
+    fn find_min_suffix<P>(
+        &mut self,
+        rng: &Range<usize>,
+        predicate: &mut P,
+        pos: &Pos,
+        carryover: V,
+    ) -> SearchOutput<V>
+    where
+        P: FnMut(&V) -> bool,
+    {
+        if pos.is_inside(rng) {
+            let v = V::op(&self.at(pos), &carryover);
+            if (*predicate)(&v) {
+                return SearchOutput::All(v);
+            }
+        }
+        if let Some(i) = pos.single_point() {
+            return SearchOutput::Index(i);
+        }
+        let (ref left, ref right) = pos.split();
+        self.push_down(pos, left, right);
+        if right.intersects(rng) {
+            match self.find_min_suffix(rng, predicate, right, carryover) {
+                SearchOutput::All(v) if left.intersects(rng) => {
+                    self.find_min_suffix(rng, predicate, left, v)
+                }
+                output => output,
+            }
+        } else {
+            self.find_min_suffix(rng, predicate, left, carryover)
         }
     }
 
     fn at(&self, pos: &Pos) -> V {
-        let (val, upd) = self.st[pos.st_i];
-        return U::apply(upd, val);
+        let (val, upd) = &self.st[pos.st_i];
+        return U::apply(upd, val, pos.range.len());
+    }
+
+    fn update_point(&mut self, i: usize, val: V, pos: &Pos) {
+        if pos.single_point().is_some() {
+            self.st[pos.st_i] = (val, U::id());
+        } else {
+            let (ref left, ref right) = pos.split();
+            self.push_down(pos, left, right);
+            if left.contains(i) {
+                self.update_point(i, val, left);
+            } else {
+                self.update_point(i, val, right);
+            }
+            self.recalc(pos, left, right);
+        }
     }
 
-    fn update(&mut self, range: &Range<usize>, upd: U, pos: &Pos) {
+    fn update(&mut self, range: &Range<usize>, upd: &U, pos: &Pos) {
         if !pos.intersects(range) {
             return;
         }
@@ -91,18 +236,18 @@ impl<V: SegTreeValue, U: LazySegTreeUpdate<V>> LazySegTree<V, U> {
     fn push_down(&mut self, pos: &Pos, left: &Pos, right: &Pos) {
         let new_val = self.at(pos);
         let (_, upd) = mem::replace(&mut self.st[pos.st_i], (new_val, U::id()));
-        self.combine_update(left, upd);
-        self.combine_update(right, upd);
+        self.combine_update(left, &upd);
+        self.combine_update(right, &upd);
     }
 
-    fn combine_update(&mut self, pos: &Pos, upd: U) {
+    fn combine_update(&mut self, pos: &Pos, upd: &U) {
         let cur = &mut self.st[pos.st_i].1;
-        *cur = U::combine(*cur, upd);
+        *cur = U::combine(cur, upd);
     }
 
     fn build(&mut self, a: &[V], pos: &Pos) {
         if let Some(i) = pos.single_point() {
-            self.st[pos.st_i].0 = a[i];
+            self.st[pos.st_i].0 = a[i].clone();
         } else {
             let (ref left, ref right) = pos.split();
             self.build(a, left);
@@ -119,6 +264,6 @@ impl<V: SegTreeValue, U: LazySegTreeUpdate<V>> LazySegTree<V, U> {
     }
 
     fn recalc(&mut self, pos: &Pos, left: &Pos, right: &Pos) {
-        self.st[pos.st_i] = (V::op(self.at(left), self.at(right)), U::id());
+        self.st[pos.st_i] = (V::op(&self.at(left), &self.at(right)), U::id());
     }
 }
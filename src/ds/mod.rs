@@ -1,8 +1,13 @@
 pub mod bitset;
 pub mod dsu;
+pub mod dynamic_seg_tree;
+pub mod fenwick;
+pub mod iterative_seg_tree;
 pub mod lazy_seg_tree;
+pub mod persistent_seg_tree;
 pub mod seg_tree;
 pub mod seg_tree_values;
+pub mod sparse_table;
 pub mod suffix_automaton;
 
 #[cfg(test)]
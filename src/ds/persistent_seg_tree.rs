@@ -0,0 +1,181 @@
+use std::ops::{Range, RangeBounds};
+
+use crate::utils::misc::{assert_range, unpack_range};
+
+use super::seg_tree::SegTreeValue;
+
+/// Version of the tree, returned by every modification
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Root(usize);
+
+struct Node<T> {
+    val: T,
+    left: usize,
+    right: usize,
+}
+
+/// Segment tree where `set` keeps the previous versions intact,
+/// each modification adds `O(log n)` nodes to the pool.
+///
+/// # Example
+/// ```
+/// seg_tree_value_sum!(Sum, i64);
+/// let mut st = PersistentSegTree::with_len(3);
+/// let v0 = st.empty_root();
+/// let v1 = st.set(v0, 1, Sum(5));
+/// assert_eq!(st.get(v0, 0..3).0, 0);
+/// assert_eq!(st.get(v1, 0..3).0, 5);
+/// ```
+pub struct PersistentSegTree<T> {
+    n: usize,
+    /// Node 0 is the shared subtree of `e()` values, it's its own child
+    nodes: Vec<Node<T>>,
+}
+
+impl<T: SegTreeValue> PersistentSegTree<T> {
+    pub fn with_len(n: usize) -> Self {
+        Self {
+            n,
+            nodes: vec![Node {
+                val: T::e(),
+                left: 0,
+                right: 0,
+            }],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Number of nodes in the pool, over all versions
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Version with `e()` in every position
+    pub fn empty_root(&self) -> Root {
+        Root(0)
+    }
+
+    pub fn build(&mut self, a: &[T]) -> Root {
+        assert_eq!(a.len(), self.n);
+        if a.is_empty() {
+            self.empty_root()
+        } else {
+            Root(self.build_node(a, 0..self.n))
+        }
+    }
+
+    pub fn get(&self, root: Root, rng_bounds: impl RangeBounds<usize>) -> T {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.n, true);
+        if rng.is_empty() {
+            T::e()
+        } else {
+            self.calc(root.0, 0..self.n, &rng)
+        }
+    }
+
+    /// Returns the new version, `root` stays unchanged
+    pub fn set(&mut self, root: Root, i: usize, val: T) -> Root {
+        assert!(i < self.n);
+        Root(self.update(root.0, 0..self.n, i, val))
+    }
+
+    /// Index of the `k`-th (0-based) element counted in `hi` but not in `lo`,
+    /// `count` is the number of elements accounted in a value.
+    /// With a tree over sorted values and versions after each prefix of the array,
+    /// `kth(versions[l], versions[r], k, ..)` is the `k`-th smallest value of `a[l..r]`.
+    pub fn kth<F>(&self, lo: Root, hi: Root, k: usize, count: F) -> Option<usize>
+    where
+        F: Fn(&T) -> usize,
+    {
+        let total = count(&self.nodes[hi.0].val) - count(&self.nodes[lo.0].val);
+        if k < total {
+            Some(self.find_kth(lo.0, hi.0, 0..self.n, k, &count))
+        } else {
+            None
+        }
+    }
+
+    fn build_node(&mut self, a: &[T], range: Range<usize>) -> usize {
+        if range.len() == 1 {
+            return self.add_node(a[range.start].clone(), 0, 0);
+        }
+        let mid = (range.start + range.end) / 2;
+        let left = self.build_node(a, range.start..mid);
+        let right = self.build_node(a, mid..range.end);
+        self.add_inner_node(left, right)
+    }
+
+    fn calc(&self, node: usize, range: Range<usize>, rng: &Range<usize>) -> T {
+        if node == 0 {
+            return T::e();
+        }
+        if rng.start <= range.start && range.end <= rng.end {
+            return self.nodes[node].val.clone();
+        }
+        let mid = (range.start + range.end) / 2;
+        let Node { left, right, .. } = self.nodes[node];
+        if rng.end <= mid {
+            self.calc(left, range.start..mid, rng)
+        } else if mid <= rng.start {
+            self.calc(right, mid..range.end, rng)
+        } else {
+            T::op(
+                &self.calc(left, range.start..mid, rng),
+                &self.calc(right, mid..range.end, rng),
+            )
+        }
+    }
+
+    fn update(&mut self, node: usize, range: Range<usize>, i: usize, val: T) -> usize {
+        if range.len() == 1 {
+            return self.add_node(val, 0, 0);
+        }
+        let mid = (range.start + range.end) / 2;
+        let Node {
+            mut left,
+            mut right,
+            ..
+        } = self.nodes[node];
+        if i < mid {
+            left = self.update(left, range.start..mid, i, val);
+        } else {
+            right = self.update(right, mid..range.end, i, val);
+        }
+        self.add_inner_node(left, right)
+    }
+
+    fn find_kth<F>(&self, lo: usize, hi: usize, range: Range<usize>, k: usize, count: &F) -> usize
+    where
+        F: Fn(&T) -> usize,
+    {
+        if range.len() == 1 {
+            return range.start;
+        }
+        let mid = (range.start + range.end) / 2;
+        let (lo, hi) = (&self.nodes[lo], &self.nodes[hi]);
+        let left_count = count(&self.nodes[hi.left].val) - count(&self.nodes[lo.left].val);
+        if k < left_count {
+            self.find_kth(lo.left, hi.left, range.start..mid, k, count)
+        } else {
+            self.find_kth(lo.right, hi.right, mid..range.end, k - left_count, count)
+        }
+    }
+
+    fn add_inner_node(&mut self, left: usize, right: usize) -> usize {
+        let val = T::op(&self.nodes[left].val, &self.nodes[right].val);
+        self.add_node(val, left, right)
+    }
+
+    fn add_node(&mut self, val: T, left: usize, right: usize) -> usize {
+        self.nodes.push(Node { val, left, right });
+        self.nodes.len() - 1
+    }
+}
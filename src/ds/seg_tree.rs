@@ -11,7 +11,7 @@ use crate::utils::misc::{assert_range, unpack_range};
 /// struct SegTreeSum(i32);
 ///
 /// impl SegTreeValue for SegTreeSum {
-///     fn op(l: Self, r: Self) -> Self {
+///     fn op(l: &Self, r: &Self) -> Self {
 ///         Self(l.0 + r.0)
 ///     }
 ///     
@@ -20,8 +20,10 @@ use crate::utils::misc::{assert_range, unpack_range};
 ///     }
 /// }
 /// ```
-pub trait SegTreeValue: Copy {
-    fn op(l: Self, r: Self) -> Self;
+///
+/// Any `Clone` type with an associative `op` over references.
+pub trait SegTreeValue: Clone {
+    fn op(l: &Self, r: &Self) -> Self;
 
     /// Identity element: op(x, e()) = op(e(), x) = x
     fn e() -> Self;
@@ -76,14 +78,14 @@ impl<T: SegTreeValue> SegTree<T> {
         mut predicate: P,
     ) -> Option<usize>
     where
-        P: FnMut(T) -> bool,
+        P: FnMut(&T) -> bool,
     {
         let rng = unpack_range(rng_bounds);
         assert_range(&rng, 0..self.n, true);
         if !rng.is_empty() {
             match self.find_max_prefix(&rng, &mut predicate, &self.root(), T::e()) {
-                MaxPrefixOutput::All(_) => Some(rng.end - 1),
-                MaxPrefixOutput::Index(i) => {
+                SearchOutput::All(_) => Some(rng.end - 1),
+                SearchOutput::Index(i) => {
                     if i > rng.start {
                         Some(i - 1)
                     } else {
@@ -96,6 +98,34 @@ impl<T: SegTreeValue> SegTree<T> {
         }
     }
 
+    /// [0 0 1 1 1 1]
+    ///  l   ^     r
+    pub fn min_suffix<P>(
+        &self,
+        rng_bounds: impl RangeBounds<usize>,
+        mut predicate: P,
+    ) -> Option<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.n, true);
+        if !rng.is_empty() {
+            match self.find_min_suffix(&rng, &mut predicate, &self.root(), T::e()) {
+                SearchOutput::All(_) => Some(rng.start),
+                SearchOutput::Index(i) => {
+                    if i + 1 < rng.end {
+                        Some(i + 1)
+                    } else {
+                        None
+                    }
+                }
+            }
+        } else {
+            None
+        }
+    }
+
     fn update(&mut self, i: usize, val: T, pos: &Pos) {
         if pos.single_point().is_some() {
             self.st[pos.st_i] = val;
@@ -112,7 +142,7 @@ impl<T: SegTreeValue> SegTree<T> {
 
     fn calc(&self, range: &Range<usize>, pos: &Pos) -> T {
         if pos.is_inside(range) {
-            return self.st[pos.st_i];
+            return self.st[pos.st_i].clone();
         }
         let (ref left, ref right) = pos.split();
         if !right.intersects(range) {
@@ -120,13 +150,13 @@ impl<T: SegTreeValue> SegTree<T> {
         } else if !left.intersects(range) {
             self.calc(range, right)
         } else {
-            T::op(self.calc(range, left), self.calc(range, right))
+            T::op(&self.calc(range, left), &self.calc(range, right))
         }
     }
 
     fn build(&mut self, a: &[T], pos: &Pos) {
         if let Some(i) = pos.single_point() {
-            self.st[pos.st_i] = a[i];
+            self.st[pos.st_i] = a[i].clone();
         } else {
             let (ref left, ref right) = pos.split();
             self.build(a, left);
@@ -141,27 +171,27 @@ impl<T: SegTreeValue> SegTree<T> {
         predicate: &mut P,
         pos: &Pos,
         carryover: T,
-    ) -> MaxPrefixOutput<T>
+    ) -> SearchOutput<T>
     where
-        P: FnMut(T) -> bool,
+        P: FnMut(&T) -> bool,
     {
         if pos.is_inside(rng) {
-            let v = T::op(carryover, self.st[pos.st_i]);
-            if (*predicate)(v) {
-                return MaxPrefixOutput::All(v);
+            let v = T::op(&carryover, &self.st[pos.st_i]);
+            if (*predicate)(&v) {
+                return SearchOutput::All(v);
             }
         }
         if let Some(i) = pos.single_point() {
-            return MaxPrefixOutput::Index(i);
+            return SearchOutput::Index(i);
         }
         let (ref left, ref right) = pos.split();
         if left.intersects(rng) {
             match self.find_max_prefix(rng, predicate, left, carryover) {
-                MaxPrefixOutput::All(v) => {
+                SearchOutput::All(v) => {
                     if right.intersects(rng) {
                         self.find_max_prefix(rng, predicate, right, v)
                     } else {
-                        MaxPrefixOutput::Index(left.range.end)
+                        SearchOutput::Index(left.range.end)
                     }
                 }
                 index => index,
@@ -171,6 +201,38 @@ impl<T: SegTreeValue> SegTree<T> {
         }
     }
 
+    fn find_min_suffix<P>(
+        &self,
+        rng: &Range<usize>,
+        predicate: &mut P,
+        pos: &Pos,
+        carryover: T,
+    ) -> SearchOutput<T>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        if pos.is_inside(rng) {
+            let v = T::op(&self.st[pos.st_i], &carryover);
+            if (*predicate)(&v) {
+                return SearchOutput::All(v);
+            }
+        }
+        if let Some(i) = pos.single_point() {
+            return SearchOutput::Index(i);
+        }
+        let (ref left, ref right) = pos.split();
+        if right.intersects(rng) {
+            match self.find_min_suffix(rng, predicate, right, carryover) {
+                SearchOutput::All(v) if left.intersects(rng) => {
+                    self.find_min_suffix(rng, predicate, left, v)
+                }
+                output => output,
+            }
+        } else {
+            self.find_min_suffix(rng, predicate, left, carryover)
+        }
+    }
+
     fn root(&self) -> Pos {
         Pos {
             st_i: 1,
@@ -179,7 +241,7 @@ impl<T: SegTreeValue> SegTree<T> {
     }
 
     fn recalc(&mut self, pos: &Pos, left: &Pos, right: &Pos) {
-        self.st[pos.st_i] = T::op(self.st[left.st_i], self.st[right.st_i]);
+        self.st[pos.st_i] = T::op(&self.st[left.st_i], &self.st[right.st_i]);
     }
 }
 
@@ -240,7 +302,9 @@ impl Pos {
     }
 }
 
-enum MaxPrefixOutput<T> {
+/// Result of a binary search: the whole range satisfies the predicate,
+/// or the index of the first element (in the search direction) that breaks it
+pub(super) enum SearchOutput<T> {
     All(T),
     Index(usize),
 }
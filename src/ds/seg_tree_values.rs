@@ -8,7 +8,7 @@ macro_rules! simple_seg_tree_value {
         struct $name($type);
 
         impl crate::ds::seg_tree::SegTreeValue for $name {
-            fn op(l: Self, r: Self) -> Self {
+            fn op(l: &Self, r: &Self) -> Self {
                 let $l = l.0;
                 let $r = r.0;
                 Self($op)
@@ -69,3 +69,152 @@ macro_rules! seg_tree_value_max {
 }
 
 pub(crate) use seg_tree_value_max;
+
+/// Update for `LazySegTree` over a value generated by the macros above (`$value(x)`),
+/// `$len` is the number of elements in the segment.
+/// Usage example: `lazy_seg_tree_update_macros` test
+macro_rules! simple_seg_tree_update {
+    (
+        $name:ident,
+        $value:ident,
+        $type:ty,
+        $upd:ident,
+        $val:ident,
+        $len:ident,
+        $apply:expr,
+        $first:ident,
+        $second:ident,
+        $combine:expr,
+        $id:expr
+    ) => {
+        #[derive(Copy, Clone)]
+        struct $name($type);
+
+        impl crate::ds::lazy_seg_tree::LazySegTreeUpdate<$value> for $name {
+            #[allow(unused_variables)]
+            fn apply(upd: &Self, val: &$value, len: usize) -> $value {
+                let $upd = upd.0;
+                let $val = val.0;
+                let $len = len;
+                $value($apply)
+            }
+
+            fn combine(upd: &Self, other: &Self) -> Self {
+                let $first = upd.0;
+                let $second = other.0;
+                Self($combine)
+            }
+
+            fn id() -> Self {
+                Self($id)
+            }
+        }
+    };
+}
+
+pub(crate) use simple_seg_tree_update;
+
+/// Range add over min/max values
+macro_rules! seg_tree_update_add {
+    ($name:ident, $value:ident, $type:ty) => {
+        $crate::ds::seg_tree_values::simple_seg_tree_update!(
+            $name,
+            $value,
+            $type,
+            upd,
+            val,
+            len,
+            val + upd,
+            a,
+            b,
+            a + b,
+            0 as $type
+        );
+    };
+}
+
+pub(crate) use seg_tree_update_add;
+
+/// Range add over sum values
+macro_rules! seg_tree_update_add_sum {
+    ($name:ident, $value:ident, $type:ty) => {
+        $crate::ds::seg_tree_values::simple_seg_tree_update!(
+            $name,
+            $value,
+            $type,
+            upd,
+            val,
+            len,
+            val + upd * len as $type,
+            a,
+            b,
+            a + b,
+            0 as $type
+        );
+    };
+}
+
+pub(crate) use seg_tree_update_add_sum;
+
+/// Range assignment over min/max values
+macro_rules! seg_tree_update_assign {
+    ($name:ident, $value:ident, $type:ty) => {
+        $crate::ds::seg_tree_values::simple_seg_tree_update!(
+            $name,
+            $value,
+            Option<$type>,
+            upd,
+            val,
+            len,
+            upd.unwrap_or(val),
+            a,
+            b,
+            b.or(a),
+            None
+        );
+    };
+}
+
+pub(crate) use seg_tree_update_assign;
+
+/// Range assignment over sum values
+macro_rules! seg_tree_update_assign_sum {
+    ($name:ident, $value:ident, $type:ty) => {
+        $crate::ds::seg_tree_values::simple_seg_tree_update!(
+            $name,
+            $value,
+            Option<$type>,
+            upd,
+            val,
+            len,
+            upd.map_or(val, |x| x * len as $type),
+            a,
+            b,
+            b.or(a),
+            None
+        );
+    };
+}
+
+pub(crate) use seg_tree_update_assign_sum;
+
+/// Range `x -> a * x + b` over sums of `Modulo` values, the update is `(a, b)`
+macro_rules! seg_tree_update_affine {
+    ($name:ident, $value:ident, $type:ty) => {
+        $crate::ds::seg_tree_values::simple_seg_tree_update!(
+            $name,
+            $value,
+            ($type, $type),
+            upd,
+            val,
+            len,
+            upd.0 * val + upd.1 * len,
+            f,
+            g,
+            (f.0 * g.0, g.0 * f.1 + g.1),
+            (<$type>::from(1u64), <$type>::from(0u64))
+        );
+    };
+}
+
+pub(crate) use seg_tree_update_affine;
@@ -0,0 +1,116 @@
+use std::ops::RangeBounds;
+
+use crate::utils::misc::{assert_range, unpack_range};
+
+use super::seg_tree::SegTreeValue;
+
+/// Marker for values with `op(x, x) = x` (min, max, gcd, ...),
+/// so that overlapping halves of a range can be combined
+///
+/// # Example
+/// ```
+/// seg_tree_value_min!(Min, i32);
+/// impl IdempotentValue for Min {}
+/// ```
+pub trait IdempotentValue: SegTreeValue {}
+
+/// Static array with `O(1)` range queries and `O(n log n)` preprocessing
+pub struct SparseTable<T> {
+    /// `table[k][i]` is the value of `i..i + 2^k`
+    table: Vec<Vec<T>>,
+}
+
+impl<T: IdempotentValue> SparseTable<T> {
+    pub fn new(a: &[T]) -> Self {
+        let mut table = vec![a.to_vec()];
+        let mut len = 1;
+        while 2 * len <= a.len() {
+            let prev = table.last().unwrap();
+            let next = (0..=a.len() - 2 * len)
+                .map(|i| T::op(&prev[i], &prev[i + len]))
+                .collect();
+            table.push(next);
+            len *= 2;
+        }
+        Self { table }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, rng_bounds: impl RangeBounds<usize>) -> T {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.len(), true);
+        if rng.is_empty() {
+            return T::e();
+        }
+        let k = floor_log2(rng.len());
+        let level = &self.table[k];
+        T::op(&level[rng.start], &level[rng.end - (1 << k)])
+    }
+}
+
+/// Same as `SparseTable` for any associative operation:
+/// each range is split into two precalculated parts that don't overlap
+pub struct DisjointSparseTable<T> {
+    a: Vec<T>,
+    /// For every block of size `2^(k + 1)`, `table[k][i]` is the value from `i`
+    /// to the middle of the block if `i` is in the left half, and from the middle to `i` otherwise
+    table: Vec<Vec<T>>,
+}
+
+impl<T: SegTreeValue> DisjointSparseTable<T> {
+    pub fn new(a: &[T]) -> Self {
+        let n = a.len();
+        let mut table = Vec::new();
+        let mut half = 1;
+        while half < n {
+            let mut level = a.to_vec();
+            for mid in (half..n).step_by(2 * half) {
+                for i in (mid - half..mid - 1).rev() {
+                    level[i] = T::op(&a[i], &level[i + 1]);
+                }
+                for i in mid + 1..std::cmp::min(mid + half, n) {
+                    level[i] = T::op(&level[i - 1], &a[i]);
+                }
+            }
+            table.push(level);
+            half *= 2;
+        }
+        Self {
+            a: a.to_vec(),
+            table,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.a.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, rng_bounds: impl RangeBounds<usize>) -> T {
+        let rng = unpack_range(rng_bounds);
+        assert_range(&rng, 0..self.len(), true);
+        if rng.is_empty() {
+            return T::e();
+        }
+        let (first, last) = (rng.start, rng.end - 1);
+        if first == last {
+            return self.a[first].clone();
+        }
+        let level = &self.table[floor_log2(first ^ last)];
+        T::op(&level[first], &level[last])
+    }
+}
+
+fn floor_log2(x: usize) -> usize {
+    (usize::BITS - 1 - x.leading_zeros()) as usize
+}
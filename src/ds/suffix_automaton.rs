@@ -1,4 +1,6 @@
-use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+use std::{collections::HashMap, hash::Hash, marker::PhantomData, ops::Range};
+
+use crate::utils::collections::IntoVecExt;
 
 pub type NodeIndex = usize;
 
@@ -7,6 +9,9 @@ const NO_NODE_INDEX: NodeIndex = usize::MAX;
 pub trait AlphabetMap<C>: Clone + Default {
     fn get(&self, c: &C) -> Option<NodeIndex>;
     fn insert(&mut self, c: C, v: NodeIndex);
+
+    /// Calls `f` for every transition, in no particular order
+    fn for_each(&self, f: impl FnMut(&C, NodeIndex));
 }
 
 #[derive(Clone)]
@@ -45,6 +50,12 @@ impl<const O: u8, const S: usize> AlphabetMap<u8> for ByteRangeMap<O, S> {
     fn insert(&mut self, c: u8, v: NodeIndex) {
         self.0[(c - O) as usize] = v;
     }
+
+    fn for_each(&self, mut f: impl FnMut(&u8, NodeIndex)) {
+        for (c, node_i) in self.nxt() {
+            f(&c, node_i);
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -64,19 +75,36 @@ impl<C: Hash + Eq + Clone> AlphabetMap<C> for AlphabetHashMap<C> {
     fn insert(&mut self, c: C, v: NodeIndex) {
         self.0.insert(c, v);
     }
+
+    fn for_each(&self, mut f: impl FnMut(&C, NodeIndex)) {
+        for (c, &node_i) in self.0.iter() {
+            f(c, node_i);
+        }
+    }
 }
 
 pub struct Node<C, M: AlphabetMap<C>> {
     pub len: usize,
     pub link: Option<NodeIndex>,
     pub nxt: M,
+    /// Number of strings added by `add_string` that contain the strings of the node
+    pub strings: usize,
+    /// Number of added positions for which the node is the longest suffix
+    ends: usize,
+    /// Id of the last string that was counted in `strings`
+    last_string: usize,
     phantom: PhantomData<C>,
 }
 
 /// https://cp-algorithms.com/string/suffix-automaton.html
+///
+/// Several strings can be added with `add_string`, then it's the generalized automaton
+/// of all their substrings.
 pub struct SuffixAutomaton<C, M: AlphabetMap<C>> {
     nodes: Vec<Node<C, M>>,
     last_i: NodeIndex,
+    /// Number of strings added by `add_string`
+    string_count: usize,
 }
 
 pub type LowerCaseSuffixAutomaton = SuffixAutomaton<u8, LowerCaseMap>;
@@ -90,11 +118,15 @@ impl<C: Copy, M: AlphabetMap<C>> SuffixAutomaton<C, M> {
             len: 0,
             link: None,
             nxt: M::default(),
+            strings: 0,
+            ends: 0,
+            last_string: usize::MAX,
             phantom: PhantomData,
         };
         Self {
             nodes: vec![root],
             last_i: Self::ROOT_NODE_I,
+            string_count: 0,
         }
     }
 
@@ -109,9 +141,45 @@ impl<C: Copy, M: AlphabetMap<C>> SuffixAutomaton<C, M> {
         }
     }
 
+    /// Makes the next `add` start a new string
+    pub fn reset_last(&mut self) {
+        self.last_i = Self::ROOT_NODE_I;
+    }
+
+    /// Adds a new string and counts it in `Node::strings`
+    pub fn add_string(&mut self, seq: impl ExactSizeIterator<Item = C>) {
+        let string_i = self.string_count;
+        self.string_count += 1;
+        self.reset_last();
+        self.nodes.reserve(2 * seq.len());
+        for c in seq {
+            self.add(c);
+            let mut node_i = self.last_i;
+            while self.nodes[node_i].last_string != string_i {
+                let node = &mut self.nodes[node_i];
+                node.last_string = string_i;
+                node.strings += 1;
+                match node.link {
+                    Some(link) => node_i = link,
+                    None => break,
+                }
+            }
+        }
+    }
+
     pub fn add(&mut self, c: C) {
         let last_i = self.last_i;
-        let cur_i = self.create_node(self.nodes[last_i].len + 1, 0, M::default());
+        if let Some(q_i) = self.nodes[last_i].nxt.get(&c) {
+            // The string was added before, possibly as a part of another string
+            self.last_i = if self.nodes[q_i].len == self.nodes[last_i].len + 1 {
+                q_i
+            } else {
+                self.split(last_i, q_i, c)
+            };
+            self.nodes[self.last_i].ends += 1;
+            return;
+        }
+        let cur_i = self.create_node(self.nodes[last_i].len + 1, 0, M::default(), 1);
         self.last_i = cur_i;
         let mut p_i = last_i;
         let q_i = loop {
@@ -126,33 +194,159 @@ impl<C: Copy, M: AlphabetMap<C>> SuffixAutomaton<C, M> {
                 return;
             };
         };
-        let (p, q) = (&self.nodes[p_i], &self.nodes[q_i]);
-        if q.len == p.len + 1 {
+        if self.nodes[q_i].len == self.nodes[p_i].len + 1 {
             self.nodes[cur_i].link = Some(q_i);
             return;
         }
-        let clone_i = self.create_node(p.len + 1, q.link.unwrap(), q.nxt.clone());
-        loop {
-            let p = &mut self.nodes[p_i];
-            if p.nxt.get(&c) == Some(q_i) {
-                p.nxt.insert(c, clone_i);
-            } else {
-                break;
-            }
-            if let Some(node_i) = p.link {
-                p_i = node_i;
-            } else {
-                break;
-            };
-        }
+        let clone_i = self.split(p_i, q_i, c);
         self.nodes[cur_i].link = Some(clone_i);
-        self.nodes[q_i].link = Some(clone_i);
     }
 
     pub fn node(&self, node_i: NodeIndex) -> &Node<C, M> {
         &self.nodes[node_i]
     }
 
+    /// Nodes sorted by `len`: links point backwards and transitions point forwards
+    pub fn topological_order(&self) -> Vec<NodeIndex> {
+        let max_len = self.nodes.iter().map(|node| node.len).max().unwrap();
+        let mut cnt = vec![0; max_len + 2];
+        for node in self.nodes.iter() {
+            cnt[node.len + 1] += 1;
+        }
+        for len in 1..cnt.len() {
+            cnt[len] += cnt[len - 1];
+        }
+        let mut res = vec![0; self.nodes.len()];
+        for (node_i, node) in self.nodes.iter().enumerate() {
+            res[cnt[node.len]] = node_i;
+            cnt[node.len] += 1;
+        }
+        res
+    }
+
+    /// Number of occurrences of the strings of each node (the size of its endpos set),
+    /// summed over all the added strings
+    pub fn occurrences(&self) -> Vec<usize> {
+        let mut res = self.nodes.iter().map(|node| node.ends).into_vec();
+        for &node_i in self.topological_order().iter().rev() {
+            if let Some(link) = self.nodes[node_i].link {
+                res[link] += res[node_i];
+            }
+        }
+        res
+    }
+
+    /// Number of distinct non-empty substrings
+    pub fn distinct_substrings(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter_map(|node| Some(node.len - self.nodes[node.link?].len))
+            .sum()
+    }
+
+    /// Total length of the distinct non-empty substrings
+    pub fn distinct_substrings_len(&self) -> usize {
+        let sum_up_to = |len: usize| len * (len + 1) / 2;
+        self.nodes
+            .iter()
+            .filter_map(|node| Some(sum_up_to(node.len) - sum_up_to(self.nodes[node.link?].len)))
+            .sum()
+    }
+
+    /// Number of distinct non-empty substrings that occur in at least `k` strings added by `add_string`
+    pub fn common_substrings(&self, k: usize) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.strings >= k)
+            .filter_map(|node| Some(node.len - self.nodes[node.link?].len))
+            .sum()
+    }
+
+    /// Length of the longest substring that occurs in at least `k` strings added by `add_string`
+    pub fn longest_common_len(&self, k: usize) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.strings >= k)
+            .map(|node| node.len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Positions in `seq` of its longest substring that is also a substring of the automaton string
+    pub fn longest_common_substring(&self, seq: impl Iterator<Item = C>) -> Range<usize> {
+        let (mut cur_i, mut cur_len) = (Self::ROOT_NODE_I, 0);
+        let mut best = 0..0;
+        for (i, c) in seq.enumerate() {
+            loop {
+                if let Some(node_i) = self.nodes[cur_i].nxt.get(&c) {
+                    cur_i = node_i;
+                    cur_len += 1;
+                    break;
+                }
+                match self.nodes[cur_i].link {
+                    Some(link) => {
+                        cur_i = link;
+                        cur_len = self.nodes[link].len;
+                    }
+                    None => {
+                        cur_len = 0;
+                        break;
+                    }
+                }
+            }
+            if cur_len > best.len() {
+                best = i + 1 - cur_len..i + 1;
+            }
+        }
+        best
+    }
+
+    /// Number of distinct strings (including the empty one) readable from each node,
+    /// used by `kth_substring`
+    pub fn substring_counts(&self) -> Vec<usize> {
+        let mut res = vec![1; self.nodes.len()];
+        for &node_i in self.topological_order().iter().rev() {
+            let mut cnt = 1;
+            self.nodes[node_i]
+                .nxt
+                .for_each(|_, next_i| cnt += res[next_i]);
+            res[node_i] = cnt;
+        }
+        res
+    }
+
+    /// `k`-th (0-based) distinct non-empty substring in lexicographic order,
+    /// `counts` are calculated by `substring_counts`
+    pub fn kth_substring(&self, counts: &[usize], mut k: usize) -> Option<Vec<C>>
+    where
+        C: Ord,
+    {
+        if k + 1 >= counts[Self::ROOT_NODE_I] {
+            return None;
+        }
+        let mut res = Vec::new();
+        let mut cur_i = Self::ROOT_NODE_I;
+        loop {
+            let mut nxt = Vec::new();
+            self.nodes[cur_i]
+                .nxt
+                .for_each(|&c, next_i| nxt.push((c, next_i)));
+            nxt.sort_unstable_by_key(|&(c, _)| c);
+            for (c, next_i) in nxt {
+                if k < counts[next_i] {
+                    res.push(c);
+                    cur_i = next_i;
+                    break;
+                }
+                k -= counts[next_i];
+            }
+            if k == 0 {
+                return Some(res);
+            }
+            k -= 1;
+        }
+    }
+
     fn find(&self, seq: impl Iterator<Item = C>) -> Option<&Node<C, M>> {
         let mut cur_i = Self::ROOT_NODE_I;
         for c in seq {
@@ -165,12 +359,40 @@ impl<C: Copy, M: AlphabetMap<C>> SuffixAutomaton<C, M> {
         Some(&self.nodes[cur_i])
     }
 
-    fn create_node(&mut self, len: usize, link: NodeIndex, nxt: M) -> NodeIndex {
+    /// Moves the strings of `q_i` that are not longer than `p_i` + `c` to a new node
+    fn split(&mut self, mut p_i: NodeIndex, q_i: NodeIndex, c: C) -> NodeIndex {
+        let (p, q) = (&self.nodes[p_i], &self.nodes[q_i]);
+        let (strings, last_string) = (q.strings, q.last_string);
+        let clone_i = self.create_node(p.len + 1, q.link.unwrap(), q.nxt.clone(), 0);
+        let clone = &mut self.nodes[clone_i];
+        clone.strings = strings;
+        clone.last_string = last_string;
+        loop {
+            let p = &mut self.nodes[p_i];
+            if p.nxt.get(&c) == Some(q_i) {
+                p.nxt.insert(c, clone_i);
+            } else {
+                break;
+            }
+            if let Some(node_i) = p.link {
+                p_i = node_i;
+            } else {
+                break;
+            };
+        }
+        self.nodes[q_i].link = Some(clone_i);
+        clone_i
+    }
+
+    fn create_node(&mut self, len: usize, link: NodeIndex, nxt: M, ends: usize) -> NodeIndex {
         let i = self.nodes.len();
         self.nodes.push(Node {
             len,
             link: Some(link),
             nxt,
+            strings: 0,
+            ends,
+            last_string: usize::MAX,
             phantom: PhantomData,
         });
         i
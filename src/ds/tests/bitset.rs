@@ -1,5 +1,5 @@
 use std::fmt::{Debug, Write};
-use std::ops::ShlAssign;
+use std::ops::{ShlAssign, ShrAssign};
 
 use crate::ds::bitset::Bitset;
 use crate::plat::classic::includes::IntoVecExt;
@@ -40,6 +40,112 @@ fn bitset_shl_random() {
     test_shl_random(150, 100);
 }
 
+#[test]
+fn bitset_shr_random() {
+    test_shr_random(1, 10);
+    test_shr_random(2, 50);
+    test_shr_random(10, 1000);
+    test_shr_random(64, 1000);
+    test_shr_random(65, 1000);
+    test_shr_random(64 * 3, 100);
+    test_shr_random(150, 100);
+}
+
+#[test]
+fn bitset_binary_ops() {
+    let mut rand = Random::new(48);
+    for n in [1, 5, 63, 64, 65, 130, 192] {
+        for _ in 0..20 {
+            let (a, naive_a) = random_bitset(&mut rand, n);
+            let (b, naive_b) = random_bitset(&mut rand, n);
+            let zip = |op: fn(bool, bool) -> bool| NaiveBitset {
+                bits: (0..n)
+                    .map(|i| op(naive_a.bits[i], naive_b.bits[i]))
+                    .collect(),
+            };
+            assert_bitset_eq(&(&a & &b), &zip(|x, y| x & y));
+            assert_bitset_eq(&(&a | &b), &zip(|x, y| x | y));
+            assert_bitset_eq(&(&a ^ &b), &zip(|x, y| x ^ y));
+            assert_bitset_eq(&!&a, &zip(|x, _| !x));
+            let mut c = a.clone();
+            c ^= &b;
+            c &= &a;
+            assert_bitset_eq(&c, &zip(|x, y| (x ^ y) & x));
+            assert_eq!((!&a).count_ones(), n - a.count_ones());
+        }
+    }
+}
+
+#[test]
+fn bitset_find_and_count() {
+    let mut rand = Random::new(49);
+    for n in [1, 5, 63, 64, 65, 130, 192] {
+        for _ in 0..20 {
+            let (a, naive) = random_bitset(&mut rand, n);
+            let ones = (0..n).filter(|&i| naive.bits[i]).into_vec();
+            assert_eq!(a.ones().into_vec(), ones);
+            assert_eq!(a.count_ones(), ones.len());
+            assert_eq!(a.any(), !ones.is_empty());
+            assert_eq!(a.none(), ones.is_empty());
+            assert_eq!(a.find_first(), ones.first().copied());
+            for i in 0..n {
+                let expected = ones.iter().copied().find(|&j| j > i);
+                assert_eq!(a.find_next(i), expected, "find_next({i}) in {a:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn bitset_resize() {
+    let mut a = Bitset::new(70);
+    a.set_all(true);
+    a.resize(65);
+    assert_eq!(a.count_ones(), 65);
+    a.resize(130);
+    assert_eq!(a.count_ones(), 65);
+    assert_eq!(a.find_next(64), None);
+    a <<= 1;
+    assert_eq!(a.ones().last(), Some(65));
+    a.resize(3);
+    assert_eq!(format!("{a:?}"), "011");
+}
+
+fn random_bitset(rand: &mut Random, n: usize) -> (Bitset, NaiveBitset) {
+    let mut actual = Bitset::new(n);
+    let mut expected = NaiveBitset::new(n);
+    let density = rand.gen_range(0..=4);
+    for i in 0..n {
+        let v = rand.gen_range(0..4) < density;
+        actual.set(i, v);
+        expected.set(i, v);
+    }
+    (actual, expected)
+}
+
+#[track_caller]
+fn test_shr_random(n: usize, iters: usize) {
+    let mut rand = Random::new(42);
+    for _ in 0..iters {
+        let (mut actual, mut expected) = random_bitset(&mut rand, n);
+        let before = expected.clone();
+        let d = rand.gen_range(0..=n);
+        if rand.gen_range(0..2) == 0 {
+            actual >>= d;
+            expected >>= d;
+            assert_bitset_op(&actual, &expected, &before, format!(">>= {d}"));
+        } else {
+            actual.shr_or(d);
+            let mut shifted = expected.clone();
+            shifted >>= d;
+            for i in 0..n {
+                expected.bits[i] |= shifted.bits[i];
+            }
+            assert_bitset_op(&actual, &expected, &before, format!("shr_or({d})"));
+        }
+    }
+}
+
 #[track_caller]
 fn test_set_random(n: usize, iters: usize) {
     let mut rand = Random::new(42);
@@ -131,6 +237,14 @@ impl ShlAssign<usize> for NaiveBitset {
     }
 }
 
+impl ShrAssign<usize> for NaiveBitset {
+    fn shr_assign(&mut self, d: usize) {
+        let n = self.len();
+        self.bits.drain(..d.min(n));
+        self.bits.resize(n, false);
+    }
+}
+
 impl Debug for NaiveBitset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for &bt in &self.bits {
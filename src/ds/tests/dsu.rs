@@ -0,0 +1,104 @@
+use crate::ds::dsu::{Dsu, PotentialDsu, RollbackDsu, Xor};
+use crate::math::modulo::Modulo;
+use crate::utils::rand::Random;
+
+/// Components as the smallest vertex of each vertex's component
+fn naive_components(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut comp: Vec<usize> = (0..n).collect();
+    for _ in 0..n {
+        for &(v, u) in edges {
+            let c = std::cmp::min(comp[v], comp[u]);
+            comp[v] = c;
+            comp[u] = c;
+        }
+    }
+    comp
+}
+
+#[test]
+fn dsu_sizes_and_groups() {
+    let mut dsu = Dsu::new(6);
+    assert!(dsu.join(0, 3));
+    assert!(dsu.join(4, 3));
+    assert!(!dsu.join(0, 4));
+    assert!(dsu.join(1, 5));
+    assert_eq!(dsu.components(), 3);
+    assert_eq!(dsu.size(4), 3);
+    assert_eq!(dsu.size(5), 2);
+    assert_eq!(dsu.size(2), 1);
+    assert_eq!(dsu.groups(), vec![vec![0, 3, 4], vec![1, 5], vec![2]]);
+}
+
+#[test]
+fn rollback_dsu_rand() {
+    let n = 12;
+    let mut rand = Random::new(46);
+    let mut dsu = RollbackDsu::new(n);
+    let mut edges = Vec::new();
+    let mut snapshots = Vec::new();
+    for _ in 0..500 {
+        match rand.gen_range(0..4) {
+            0 => snapshots.push((dsu.snapshot(), edges.len())),
+            1 if !snapshots.is_empty() => {
+                let (snapshot, len) = snapshots.remove(rand.gen_range(0..snapshots.len()));
+                snapshots.retain(|&(_, other_len)| other_len <= len);
+                dsu.rollback(snapshot);
+                edges.truncate(len);
+            }
+            _ => {
+                let (v, u) = (rand.gen_range(0..n), rand.gen_range(0..n));
+                dsu.join(v, u);
+                edges.push((v, u));
+            }
+        }
+        let comp = naive_components(n, &edges);
+        for v in 0..n {
+            for u in 0..n {
+                assert_eq!(dsu.parent(v) == dsu.parent(u), comp[v] == comp[u]);
+            }
+            assert_eq!(dsu.size(v), comp.iter().filter(|&&c| c == comp[v]).count());
+        }
+        assert_eq!(dsu.components(), (0..n).filter(|&v| comp[v] == v).count());
+    }
+}
+
+#[test]
+fn potential_dsu_consistency() {
+    let n = 10;
+    let mut rand = Random::new(47);
+    let x = (0..n)
+        .map(|_| rand.gen_range(0..100) as i64 - 50)
+        .collect::<Vec<_>>();
+    let mut dsu = PotentialDsu::new(n);
+    let mut edges = Vec::new();
+    for _ in 0..200 {
+        let (v, u) = (rand.gen_range(0..n), rand.gen_range(0..n));
+        let comp = naive_components(n, &edges);
+        let connected = comp[v] == comp[u];
+        assert_eq!(dsu.diff(v, u), Some(x[v] - x[u]).filter(|_| connected));
+        if connected && rand.gen_range(0..2) == 0 {
+            assert!(!dsu.join(v, u, x[v] - x[u] + 1));
+        } else {
+            assert!(dsu.join(v, u, x[v] - x[u]));
+            edges.push((v, u));
+        }
+    }
+}
+
+#[test]
+fn potential_dsu_groups() {
+    let mut dsu = PotentialDsu::new(4);
+    assert!(dsu.join(0, 1, Xor(0b101)));
+    assert!(dsu.join(2, 1, Xor(0b011)));
+    assert_eq!(dsu.diff(0, 2), Some(Xor(0b110)));
+    assert!(!dsu.join(2, 0, Xor(0b111)));
+    assert_eq!(dsu.diff(3, 0), None);
+
+    type Mod = Modulo<7>;
+    let mut dsu = PotentialDsu::new(3);
+    assert!(dsu.join(0, 1, Mod::new(5)));
+    assert!(dsu.join(1, 2, Mod::new(4)));
+    assert_eq!(dsu.diff(0, 2), Some(Mod::new(2)));
+    assert_eq!(dsu.diff(2, 0), Some(Mod::new(5)));
+    assert!(dsu.join(2, 0, Mod::new(12)));
+}
@@ -0,0 +1,91 @@
+use std::ops::Bound;
+
+use crate::ds::dynamic_seg_tree::DynamicSegTree;
+use crate::ds::seg_tree_values::{
+    seg_tree_update_add_sum, seg_tree_update_assign, seg_tree_value_min, seg_tree_value_sum,
+};
+use crate::utils::rand::Random;
+
+#[test]
+fn dynamic_seg_tree_huge_range() {
+    seg_tree_value_sum!(Sum, i64);
+    seg_tree_update_add_sum!(Add, Sum, i64);
+    const MAX: i64 = 1_000_000_000_000_000_000;
+    let mut st = DynamicSegTree::<Sum>::with_range(-MAX..MAX);
+    st.set(-MAX, Sum(1));
+    st.set(123_456_789_012_345, Sum(2));
+    st.set(MAX - 1, Sum(4));
+    assert_eq!(st.get(..).0, 7);
+    assert_eq!(st.get(-MAX + 1..MAX - 1).0, 2);
+    assert_eq!(st.get(..=123_456_789_012_345).0, 3);
+    assert!(st.node_count() < 3 * 2 * 64);
+
+    let mut st = DynamicSegTree::<Sum, Add>::with_range(0..MAX);
+    st.apply(10..MAX, Add(3));
+    st.set(20, Sum(-1));
+    st.apply(..=20, Add(1));
+    assert_eq!(st.get(0..30).0, 10 + 10 * 4 - 1 + 1 + 9 * 3);
+    assert_eq!(st.get(MAX - 5..).0, 15);
+}
+
+#[test]
+fn dynamic_seg_tree_full_key_space() {
+    seg_tree_value_sum!(Sum, i64);
+    let mut st = DynamicSegTree::<Sum>::with_range(i64::MIN..i64::MAX);
+    st.set(i64::MIN, Sum(1));
+    st.set(i64::MAX - 1, Sum(2));
+    st.set(0, Sum(4));
+    assert_eq!(st.get(..).0, 7);
+    assert_eq!(st.get(..=i64::MAX - 1).0, 7);
+    assert_eq!(st.get(i64::MIN..=0).0, 5);
+    assert_eq!(st.get((Bound::Excluded(i64::MIN), Bound::Unbounded)).0, 6);
+}
+
+#[test]
+#[should_panic(expected = "range does not meet")]
+fn dynamic_seg_tree_overflowing_bound() {
+    seg_tree_value_sum!(Sum, i64);
+    let st = DynamicSegTree::<Sum>::with_range(i64::MIN..i64::MAX);
+    st.get(0..=i64::MAX);
+}
+
+#[test]
+fn dynamic_seg_tree_rand() {
+    seg_tree_value_sum!(Sum, i64);
+    seg_tree_update_add_sum!(Add, Sum, i64);
+    seg_tree_value_min!(Min, i64);
+    seg_tree_update_assign!(Assign, Min, i64);
+    let mut rand = Random::new(13);
+    let (lo, hi) = (-20i64, 23i64);
+    let mut state = vec![0i64; (hi - lo) as usize];
+    let mut sums = DynamicSegTree::<Sum, Add>::with_range(lo..hi);
+    let mut mins = DynamicSegTree::<Min, Assign>::with_range(lo..hi);
+    let mut min_state = vec![i64::MAX; (hi - lo) as usize];
+    for _ in 0..2000 {
+        let start = rand.gen_range(0..state.len());
+        let end = rand.gen_range(start..=state.len());
+        let rng = start as i64 + lo..end as i64 + lo;
+        let v = rand.gen_range(0..100) as i64 - 50;
+        match rand.gen_range(0..4) {
+            0 => {
+                state[start..end].iter_mut().for_each(|x| *x += v);
+                sums.apply(rng, Add(v));
+                min_state[start..end].iter_mut().for_each(|x| *x = v);
+                mins.apply(start as i64 + lo..end as i64 + lo, Assign(Some(v)));
+            }
+            1 if start < state.len() => {
+                state[start] = v;
+                sums.set(rng.start, Sum(v));
+                min_state[start] = v;
+                mins.set(rng.start, Min(v));
+            }
+            _ => {
+                let expected: i64 = state[start..end].iter().sum();
+                assert_eq!(sums.get(rng.clone()).0, expected, "sum rng: {rng:?}");
+                let expected = min_state[start..end].iter().min().copied();
+                let expected = expected.unwrap_or(i64::MAX);
+                assert_eq!(mins.get(rng.clone()).0, expected, "min rng: {rng:?}");
+            }
+        }
+    }
+}
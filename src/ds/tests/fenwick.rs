@@ -0,0 +1,94 @@
+use crate::ds::fenwick::{Fenwick, Fenwick2D, RangeFenwick};
+use crate::math::modulo::Modulo;
+use crate::utils::rand::Random;
+
+#[test]
+fn fenwick_point_add() {
+    let mut rand = Random::new(21);
+    for n in 0..12 {
+        let mut state: Vec<i64> = (0..n).map(|_| rand.gen_range(0..20) as i64 - 10).collect();
+        let mut fenwick = Fenwick::new(&state);
+        assert_eq!(fenwick.len(), n);
+        for _ in 0..100 {
+            if n > 0 && rand.gen_range(0..2) == 0 {
+                let i = rand.gen_range(0..n);
+                let v = rand.gen_range(0..20) as i64 - 10;
+                state[i] += v;
+                fenwick.add(i, v);
+            }
+            let l = rand.gen_range(0..=n);
+            let r = rand.gen_range(l..=n);
+            let expected: i64 = state[l..r].iter().sum();
+            assert_eq!(
+                fenwick.get(l..r),
+                expected,
+                "rng: {:?}, a = {state:?}",
+                l..r
+            );
+        }
+    }
+}
+
+#[test]
+fn fenwick_lower_bound() {
+    let counts = [2usize, 0, 1, 0, 0, 3, 1];
+    let fenwick = Fenwick::new(&counts);
+    let sorted = [0, 0, 2, 5, 5, 5, 6];
+    for (k, &expected) in sorted.iter().enumerate() {
+        assert_eq!(fenwick.lower_bound(k + 1), expected, "k: {k}");
+    }
+    assert_eq!(fenwick.lower_bound(0), 0);
+    assert_eq!(fenwick.lower_bound(sorted.len() + 1), counts.len());
+    assert_eq!(Fenwick::<usize>::with_len(0).lower_bound(1), 0);
+}
+
+#[test]
+fn fenwick_range_add() {
+    type Mod = Modulo<1_000_000_007>;
+    let mut rand = Random::new(4);
+    let n = 15;
+    let mut state: Vec<i64> = (0..n).map(|i| i as i64 * 3 - 20).collect();
+    let mut fenwick = RangeFenwick::new(&state);
+    let mut state_mod: Vec<Mod> = (0..n).map(|_| Mod::new(1_000_000_000)).collect();
+    let mut fenwick_mod = RangeFenwick::new(&state_mod);
+    for _ in 0..500 {
+        let l = rand.gen_range(0..=n);
+        let r = rand.gen_range(l..=n);
+        if rand.gen_range(0..2) == 0 {
+            let v = rand.gen_range(0..100) as i64 - 50;
+            state[l..r].iter_mut().for_each(|x| *x += v);
+            fenwick.add(l..r, v);
+            let v = Mod::new(rand.gen() as u64);
+            state_mod[l..r].iter_mut().for_each(|x| *x += v);
+            fenwick_mod.add(l..r, v);
+        } else {
+            let expected: i64 = state[l..r].iter().sum();
+            assert_eq!(fenwick.get(l..r), expected, "rng: {:?}", l..r);
+            let expected: Mod = state_mod[l..r].iter().copied().sum();
+            assert_eq!(fenwick_mod.get(l..r), expected, "rng: {:?}", l..r);
+        }
+    }
+}
+
+#[test]
+fn fenwick_2d() {
+    let mut rand = Random::new(9);
+    let (n, m) = (5, 7);
+    let mut state = vec![vec![0i64; m]; n];
+    let mut fenwick = Fenwick2D::with_size(n, m);
+    for _ in 0..300 {
+        let (i, j) = (rand.gen_range(0..n), rand.gen_range(0..m));
+        let v = rand.gen_range(0..10) as i64 - 3;
+        state[i][j] += v;
+        fenwick.add(i, j, v);
+        let r1 = rand.gen_range(0..=n);
+        let r2 = rand.gen_range(r1..=n);
+        let c1 = rand.gen_range(0..=m);
+        let c2 = rand.gen_range(c1..=m);
+        let expected: i64 = state[r1..r2]
+            .iter()
+            .map(|row| row[c1..c2].iter().sum::<i64>())
+            .sum();
+        assert_eq!(fenwick.get(r1..r2, c1..c2), expected);
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::Instant;
+
+use crate::ds::iterative_seg_tree::IterativeSegTree;
+use crate::ds::seg_tree::SegTree;
+use crate::ds::seg_tree_values::{seg_tree_value_max, seg_tree_value_sum};
+use crate::plat::classic::includes::IntoVecExt;
+use crate::utils::rand::Random;
+
+#[test]
+fn iterative_seg_tree_sum() {
+    seg_tree_value_sum!(Element, i64);
+    let mut rand = Random::new(17);
+    for n in 0..20 {
+        let mut cur: Vec<i64> = (0..n).map(|_| rand.gen_range(0..100) as i64 - 50).collect();
+        let mut st = IterativeSegTree::new(&cur.iter().map(|&x| Element(x)).into_vec());
+        assert_eq!(st.len(), n);
+        for _ in 0..50 {
+            if n > 0 && rand.gen_range(0..3) == 0 {
+                let i = rand.gen_range(0..n);
+                cur[i] = rand.gen_range(0..100) as i64;
+                st.set(i, Element(cur[i]));
+            }
+            let l = rand.gen_range(0..=n);
+            let r = rand.gen_range(l..=n);
+            let expected: i64 = cur[l..r].iter().sum();
+            assert_eq!(st.get(l..r).0, expected, "rng: {:?}, a = {cur:?}", l..r);
+        }
+    }
+}
+
+#[test]
+fn iterative_seg_tree_searches() {
+    seg_tree_value_max!(Element, i32);
+    const DATA: [i32; 19] = [1, 10, 5, 6, -5, 4, 3, 13, 3, 4, 8, 7, 9, 10, 8, 9, 9, 1, 2];
+    let (mn, mx) = (*DATA.iter().min().unwrap(), *DATA.iter().max().unwrap());
+    let n = DATA.len();
+    for len in [1, 2, 5, 8, n] {
+        let a = &DATA[..len];
+        let st = IterativeSegTree::new(&a.iter().map(|&x| Element(x)).into_vec());
+        for l in 0..len {
+            for r in l..=len {
+                for v in mn..=mx + 1 {
+                    let max_prefix = (l..r).take_while(|&i| a[l..=i].iter().all(|&x| x < v));
+                    assert_eq!(
+                        st.max_prefix(l..r, |el| el.0 < v),
+                        max_prefix.last(),
+                        "max_prefix rng: {:?}, v: {v}",
+                        l..r
+                    );
+                    let min_suffix = (l..r).rev().take_while(|&i| a[i..r].iter().all(|&x| x < v));
+                    assert_eq!(
+                        st.min_suffix(l..r, |el| el.0 < v),
+                        min_suffix.last(),
+                        "min_suffix rng: {:?}, v: {v}",
+                        l..r
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// `cargo test --release iterative_seg_tree_bench -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn iterative_seg_tree_bench() {
+    seg_tree_value_sum!(Element, u64);
+    const N: usize = 1 << 20;
+    const QUERIES: usize = 2_000_000;
+    let mut rand = Random::new(1);
+    let a = (0..N)
+        .map(|_| Element(rand.gen_range(0..1000) as u64))
+        .into_vec();
+    let queries = (0..QUERIES)
+        .map(|_| {
+            let l = rand.gen_range(0..N);
+            (l, rand.gen_range(l..=N), rand.gen_range(0..1000) as u64)
+        })
+        .into_vec();
+
+    let start = Instant::now();
+    let mut st = SegTree::new(&a);
+    let mut recursive = 0;
+    for &(l, r, v) in queries.iter() {
+        recursive ^= st.get(l..r).0;
+        st.set(l, Element(v));
+    }
+    let recursive_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut st = IterativeSegTree::new(&a);
+    let mut iterative = 0;
+    for &(l, r, v) in queries.iter() {
+        iterative ^= st.get(l..r).0;
+        st.set(l, Element(v));
+    }
+    let iterative_time = start.elapsed();
+
+    assert_eq!(recursive, iterative);
+    println!("SegTree: {recursive_time:?}, IterativeSegTree: {iterative_time:?}");
+}
@@ -8,7 +8,7 @@ use crate::utils::rand::Random;
 struct Element(usize);
 
 impl SegTreeValue for Element {
-    fn op(l: Self, r: Self) -> Self {
+    fn op(l: &Self, r: &Self) -> Self {
         Element(std::cmp::max(l.0, r.0))
     }
 
@@ -21,11 +21,11 @@ impl SegTreeValue for Element {
 struct Update(usize);
 
 impl LazySegTreeUpdate<Element> for Update {
-    fn apply(upd: Self, val: Element) -> Element {
+    fn apply(upd: &Self, val: &Element, _len: usize) -> Element {
         Element(val.0 + upd.0)
     }
 
-    fn combine(upd: Self, other: Self) -> Self {
+    fn combine(upd: &Self, other: &Self) -> Self {
         Self(upd.0 + other.0)
     }
 
@@ -76,8 +76,111 @@ fn run_rand_test(n: usize, iters: usize, seed: usize) {
     }
 }
 
+#[test]
+fn lazy_seg_tree_searches() {
+    let n = 13;
+    let mut rand = Random::new(11);
+    let mut state = vec![0usize; n];
+    let mut seg_tree = LazySegTree::with_size(n);
+    for _ in 0..300 {
+        let start = rand.gen_range(0..n);
+        let end = rand.gen_range(start..=n);
+        if rand.gen_range(0..2) == 0 {
+            let upd = rand.gen_range(1..5);
+            for x in state[start..end].iter_mut() {
+                *x += upd;
+            }
+            seg_tree.apply(start..end, Update(upd));
+            continue;
+        }
+        for v in 0..=state.iter().max().unwrap() + 1 {
+            let expected = (start..end)
+                .take_while(|&i| state[start..=i].iter().all(|&x| x < v))
+                .last();
+            let actual = seg_tree.max_prefix(start..end, |el| el.0 < v);
+            assert_eq!(actual, expected, "max_prefix rng: {:?}, v: {v}", start..end);
+            let expected = (start..end)
+                .rev()
+                .take_while(|&i| state[i..end].iter().all(|&x| x < v))
+                .last();
+            let actual = seg_tree.min_suffix(start..end, |el| el.0 < v);
+            assert_eq!(actual, expected, "min_suffix rng: {:?}, v: {v}", start..end);
+        }
+    }
+}
+
 #[track_caller]
 fn assert_get(seg_tree: &mut LazySegTree<Element, Update>, rng: Range<usize>, expected: usize) {
     let actual = seg_tree.get(rng.clone()).0;
     assert_eq!(actual, expected, "get failed for range {rng:?}")
 }
+
+/// Counts of the values by remainder modulo 3
+#[derive(Clone, PartialEq, Debug)]
+struct Counts(Vec<usize>);
+
+impl SegTreeValue for Counts {
+    fn op(l: &Self, r: &Self) -> Self {
+        Counts(l.0.iter().zip(r.0.iter()).map(|(x, y)| x + y).collect())
+    }
+
+    fn e() -> Self {
+        Counts(vec![0; 3])
+    }
+}
+
+/// Remainder `i` becomes `self.0[i]`
+#[derive(Clone)]
+struct Remap(Vec<usize>);
+
+impl LazySegTreeUpdate<Counts> for Remap {
+    fn apply(upd: &Self, val: &Counts, _len: usize) -> Counts {
+        let mut res = vec![0; 3];
+        for (i, &cnt) in val.0.iter().enumerate() {
+            res[upd.0[i]] += cnt;
+        }
+        Counts(res)
+    }
+
+    fn combine(upd: &Self, other: &Self) -> Self {
+        Remap(upd.0.iter().map(|&i| other.0[i]).collect())
+    }
+
+    fn id() -> Self {
+        Remap(vec![0, 1, 2])
+    }
+}
+
+#[test]
+fn lazy_seg_tree_non_copy() {
+    let n = 12;
+    let mut rand = Random::new(3);
+    let mut state: Vec<usize> = (0..n).map(|i| i % 3).collect();
+    let mut seg_tree = LazySegTree::new(
+        &state
+            .iter()
+            .map(|&x| {
+                let mut counts = Counts::e();
+                counts.0[x] = 1;
+                counts
+            })
+            .collect::<Vec<_>>(),
+    );
+    for _ in 0..1000 {
+        let start = rand.gen_range(0..n);
+        let end = rand.gen_range(start + 1..=n);
+        if rand.gen_range(0..3) == 0 {
+            let remap: Vec<_> = (0..3).map(|_| rand.gen_range(0..3)).collect();
+            for x in state[start..end].iter_mut() {
+                *x = remap[*x];
+            }
+            seg_tree.apply(start..end, Remap(remap));
+        } else {
+            let mut expected = Counts::e();
+            for &x in state[start..end].iter() {
+                expected.0[x] += 1;
+            }
+            assert_eq!(seg_tree.get(start..end), expected, "rng: {:?}", start..end);
+        }
+    }
+}
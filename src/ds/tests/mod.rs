@@ -1,5 +1,11 @@
 mod bitset;
+mod dsu;
+mod dynamic_seg_tree;
+mod fenwick;
+mod iterative_seg_tree;
 mod lazy_seg_tree;
+mod persistent_seg_tree;
 mod seg_tree;
 mod seg_tree_values;
+mod sparse_table;
 mod suffix_automaton;
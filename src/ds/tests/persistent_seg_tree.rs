@@ -0,0 +1,59 @@
+use crate::ds::persistent_seg_tree::PersistentSegTree;
+use crate::ds::seg_tree_values::seg_tree_value_sum;
+use crate::plat::classic::includes::IntoVecExt;
+use crate::utils::rand::Random;
+
+#[test]
+fn persistent_seg_tree_versions() {
+    seg_tree_value_sum!(Sum, i64);
+    let mut rand = Random::new(8);
+    let n = 11;
+    let mut st = PersistentSegTree::with_len(n);
+    let a = (0..n).map(|i| i as i64).into_vec();
+    let mut versions = vec![(a.clone(), st.build(&a.iter().map(|&x| Sum(x)).into_vec()))];
+    for _ in 0..100 {
+        let (mut cur, root) = versions[rand.gen_range(0..versions.len())].clone();
+        let i = rand.gen_range(0..n);
+        cur[i] = rand.gen_range(0..100) as i64;
+        let root = st.set(root, i, Sum(cur[i]));
+        versions.push((cur, root));
+    }
+    for (a, root) in versions {
+        for l in 0..=n {
+            for r in l..=n {
+                let expected: i64 = a[l..r].iter().sum();
+                assert_eq!(st.get(root, l..r).0, expected, "rng: {:?}, a = {a:?}", l..r);
+            }
+        }
+    }
+}
+
+#[test]
+fn persistent_seg_tree_kth_smallest() {
+    seg_tree_value_sum!(Count, usize);
+    let a = [5, 1, 4, 4, 8, 0, 3, 7, 1, 6];
+    let mut sorted = a.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    let mut st = PersistentSegTree::<Count>::with_len(sorted.len());
+    let mut versions = vec![st.empty_root()];
+    for x in a {
+        let i = sorted.binary_search(&x).unwrap();
+        let root = *versions.last().unwrap();
+        let cnt = st.get(root, i..=i).0;
+        versions.push(st.set(root, i, Count(cnt + 1)));
+    }
+    for l in 0..a.len() {
+        for r in l..=a.len() {
+            let mut expected = a[l..r].to_vec();
+            expected.sort();
+            for k in 0..=r - l {
+                let actual = st
+                    .kth(versions[l], versions[r], k, |cnt| cnt.0)
+                    .map(|i| sorted[i]);
+                assert_eq!(actual, expected.get(k).copied(), "rng: {:?}, k: {k}", l..r);
+            }
+        }
+    }
+    assert!(st.node_count() <= 1 + a.len() * 4);
+}
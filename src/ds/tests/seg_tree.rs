@@ -10,7 +10,7 @@ fn seg_tree_example() {
     struct SegTreeEl(i32);
 
     impl SegTreeValue for SegTreeEl {
-        fn op(l: Self, r: Self) -> Self {
+        fn op(l: &Self, r: &Self) -> Self {
             Self(l.0 + r.0)
         }
 
@@ -65,6 +65,27 @@ fn seg_tree_max_prefix() {
     }
 }
 
+#[test]
+fn seg_tree_min_suffix() {
+    seg_tree_value_max!(Element, i32);
+    const DATA: [i32; 18] = [1, 10, 5, 6, -5, 4, 3, 13, 3, 4, 8, 7, 9, 10, 8, 9, 9, 1];
+    let (mn, mx) = (*DATA.iter().min().unwrap(), *DATA.iter().max().unwrap());
+    let st = SegTree::new(&DATA.iter().map(|x| Element(*x)).into_vec());
+    let n = DATA.len();
+    for l in 0..n {
+        for r in l..=n {
+            for v in mn..=mx + 1 {
+                let expected = (l..r)
+                    .rev()
+                    .take_while(|&i| DATA[i..r].iter().all(|&x| x < v))
+                    .last();
+                let actual = st.min_suffix(l..r, |el| el.0 < v);
+                assert_eq!(actual, expected, "rng: {:?}, v: {v}", l..r);
+            }
+        }
+    }
+}
+
 #[track_caller]
 fn check_seg_tree_max_prefix(a: &[i32], rng: Range<usize>, v: i32) {
     seg_tree_value_max!(Element, i32);
@@ -91,3 +112,37 @@ fn check_seg_tree_max_prefix(a: &[i32], rng: Range<usize>, v: i32) {
         &a[rng]
     );
 }
+
+#[test]
+fn seg_tree_non_copy() {
+    /// Sorted values of the segment
+    #[derive(Clone)]
+    struct Sorted(Vec<i32>);
+
+    impl SegTreeValue for Sorted {
+        fn op(l: &Self, r: &Self) -> Self {
+            let mut res = [&l.0[..], &r.0[..]].concat();
+            res.sort();
+            Self(res)
+        }
+
+        fn e() -> Self {
+            Self(Vec::new())
+        }
+    }
+
+    let mut a = vec![5, 1, 4, 4, -2, 8, 0, 3, 7, 1];
+    let mut st = SegTree::new(&a.iter().map(|&x| Sorted(vec![x])).into_vec());
+    a[3] = 6;
+    st.set(3, Sorted(vec![6]));
+    for l in 0..a.len() {
+        for r in l..=a.len() {
+            let mut expected = a[l..r].to_vec();
+            expected.sort();
+            assert_eq!(st.get(l..r).0, expected, "rng: {:?}", l..r);
+        }
+    }
+    let with_small = |el: &Sorted| el.0.iter().filter(|&&x| x < 2).count() < 2;
+    assert_eq!(st.max_prefix(0..a.len(), with_small), Some(3));
+    assert_eq!(st.max_prefix(6..a.len(), with_small), Some(8));
+}
@@ -1,6 +1,12 @@
+use crate::ds::lazy_seg_tree::LazySegTree;
 use crate::ds::seg_tree_values::{
-    seg_tree_value_max, seg_tree_value_min, seg_tree_value_sum, simple_seg_tree_value,
+    seg_tree_update_add, seg_tree_update_add_sum, seg_tree_update_affine, seg_tree_update_assign,
+    seg_tree_update_assign_sum, seg_tree_value_max, seg_tree_value_min, seg_tree_value_sum,
+    simple_seg_tree_value,
 };
+use crate::math::modulo::Modulo;
+use crate::plat::classic::includes::IntoVecExt;
+use crate::utils::rand::Random;
 
 #[test]
 fn seg_tree_value_macros() {
@@ -12,3 +18,68 @@ fn seg_tree_value_macros() {
     seg_tree_value_max!(ElementMaxInt, u64);
     seg_tree_value_max!(ElementMaxTuple, (u64, u64), (0, 0));
 }
+
+#[test]
+fn lazy_seg_tree_update_macros() {
+    seg_tree_value_sum!(Sum, i64);
+    seg_tree_value_min!(Min, i64);
+    seg_tree_update_add_sum!(AddSum, Sum, i64);
+    seg_tree_update_add!(AddMin, Min, i64);
+    seg_tree_update_assign_sum!(AssignSum, Sum, i64);
+    seg_tree_update_assign!(AssignMin, Min, i64);
+
+    let a = [3, -1, 4, 1, -5, 9, 2, 6];
+    let mut sums = LazySegTree::new(&a.iter().map(|&x| Sum(x)).into_vec());
+    let mut mins = LazySegTree::new(&a.iter().map(|&x| Min(x)).into_vec());
+    sums.apply(1..6, AddSum(2));
+    mins.apply(1..6, AddMin(2));
+    // [3, 1, 6, 3, -3, 11, 2, 6]
+    assert_eq!(sums.get(0..4).0, 13);
+    assert_eq!(mins.get(2..8).0, -3);
+    let mut sums = LazySegTree::new(&a.iter().map(|&x| Sum(x)).into_vec());
+    let mut mins = LazySegTree::new(&a.iter().map(|&x| Min(x)).into_vec());
+    sums.apply(2..5, AssignSum(Some(7)));
+    mins.apply(2..5, AssignMin(Some(7)));
+    // [3, -1, 7, 7, 7, 9, 2, 6]
+    assert_eq!(sums.get(0..8).0, 40);
+    assert_eq!(mins.get(2..7).0, 2);
+    sums.apply(0..3, AssignSum(Some(1)));
+    // [1, 1, 1, 7, 7, 9, 2, 6]
+    assert_eq!(sums.get(2..4).0, 8);
+    assert_eq!(sums.len(), 8);
+}
+
+#[test]
+fn lazy_seg_tree_affine() {
+    type Mod = Modulo<998_244_353>;
+    seg_tree_value_sum!(Sum, Mod, Mod::new(0));
+    seg_tree_update_affine!(Affine, Sum, Mod);
+
+    let mut rand = Random::new(5);
+    let n = 10;
+    let mut state = (0..n).map(|i| Mod::new(i as u64)).into_vec();
+    let mut st = LazySegTree::new(&state.iter().map(|&x| Sum(x)).into_vec());
+    for _ in 0..200 {
+        let start = rand.gen_range(0..n);
+        let end = rand.gen_range(start..=n);
+        match rand.gen_range(0..3) {
+            0 => {
+                let (a, b) = (rand.gen() as u64, rand.gen() as u64);
+                for x in state[start..end].iter_mut() {
+                    *x = Mod::new(a) * *x + Mod::new(b);
+                }
+                st.apply(start..end, Affine((Mod::new(a), Mod::new(b))));
+            }
+            1 if start < n => {
+                let v = Mod::new(rand.gen() as u64);
+                state[start] = v;
+                st.set(start, Sum(v));
+                assert_eq!(st.get_point(start).0, v);
+            }
+            _ => {
+                let expected: Mod = state[start..end].iter().copied().sum();
+                assert_eq!(st.get(start..end).0, expected, "rng: {:?}", start..end);
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::ds::seg_tree::SegTreeValue;
+use crate::ds::seg_tree_values::{seg_tree_value_min, seg_tree_value_sum};
+use crate::ds::sparse_table::{DisjointSparseTable, IdempotentValue, SparseTable};
+use crate::plat::classic::includes::IntoVecExt;
+use crate::utils::rand::Random;
+
+#[test]
+fn sparse_table_min() {
+    seg_tree_value_min!(Min, i32);
+    impl IdempotentValue for Min {}
+
+    let mut rand = Random::new(31);
+    for n in 0..40 {
+        let a = (0..n).map(|_| rand.gen_range(0..100) as i32).into_vec();
+        let table = SparseTable::new(&a.iter().map(|&x| Min(x)).into_vec());
+        assert_eq!(table.len(), n);
+        for l in 0..=n {
+            for r in l..=n {
+                let expected = a[l..r].iter().min().copied().unwrap_or(i32::MAX);
+                assert_eq!(table.get(l..r).0, expected, "rng: {:?}", l..r);
+            }
+        }
+    }
+}
+
+#[test]
+fn disjoint_sparse_table_sum() {
+    seg_tree_value_sum!(Sum, i64);
+    let mut rand = Random::new(32);
+    for n in 0..40 {
+        let a = (0..n)
+            .map(|_| rand.gen_range(0..100) as i64 - 50)
+            .into_vec();
+        let table = DisjointSparseTable::new(&a.iter().map(|&x| Sum(x)).into_vec());
+        for l in 0..=n {
+            for r in l..=n {
+                let expected: i64 = a[l..r].iter().sum();
+                assert_eq!(table.get(l..r).0, expected, "rng: {:?}", l..r);
+            }
+        }
+    }
+}
+
+#[test]
+fn disjoint_sparse_table_non_commutative() {
+    #[derive(Clone)]
+    struct Concat(String);
+
+    impl SegTreeValue for Concat {
+        fn op(l: &Self, r: &Self) -> Self {
+            Self(l.0.clone() + &r.0)
+        }
+
+        fn e() -> Self {
+            Self(String::new())
+        }
+    }
+
+    let s = "persistent_disjoint";
+    let table = DisjointSparseTable::new(&s.chars().map(|c| Concat(c.to_string())).into_vec());
+    for l in 0..=s.len() {
+        for r in l..=s.len() {
+            assert_eq!(table.get(l..r).0, &s[l..r]);
+        }
+    }
+}
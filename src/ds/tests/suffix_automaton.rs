@@ -1,7 +1,9 @@
 use std::{collections::HashSet, ops::Range};
 
 use crate::{
-    ds::suffix_automaton::LowerCaseSuffixAutomaton, types::str::Str, utils::collections::IntoVecExt,
+    ds::suffix_automaton::{AlphabetMap, HashMapSuffixAutomaton, LowerCaseSuffixAutomaton},
+    types::str::Str,
+    utils::collections::IntoVecExt,
 };
 
 #[test]
@@ -12,6 +14,190 @@ fn contains() {
     }
 }
 
+#[test]
+fn analytics() {
+    let alphabet = b'a'..b'd';
+    for s in generate_all_strings(alphabet.clone(), 6) {
+        check_analytics(&s);
+    }
+}
+
+#[test]
+fn longest_common_substring() {
+    let alphabet = b'a'..b'c';
+    let strings = generate_all_strings(alphabet, 5);
+    for s in strings.iter() {
+        let mut automaton = LowerCaseSuffixAutomaton::new();
+        automaton.add_all(s.iter().cloned());
+        let substrings = generate_all_substrings(s);
+        for t in strings.iter().step_by(3) {
+            let expected = generate_all_substrings(t)
+                .into_iter()
+                .filter(|sub| substrings.contains(sub))
+                .map(|sub| sub.len())
+                .max()
+                .unwrap();
+            let actual = automaton.longest_common_substring(t.iter().cloned());
+            assert_eq!(actual.len(), expected, "'{s}' and '{t}'");
+            assert!(substrings.contains(&Str::from(&t[actual.clone()])));
+        }
+    }
+}
+
+#[test]
+fn hash_map_analytics() {
+    let s = "mississippi".chars().into_vec();
+    let mut automaton = HashMapSuffixAutomaton::new();
+    automaton.add_all(s.iter().cloned());
+    assert_eq!(automaton.distinct_substrings(), 53);
+    let counts = automaton.substring_counts();
+    let kth = |k| {
+        automaton
+            .kth_substring(&counts, k)
+            .map(|sub| sub.iter().collect::<String>())
+    };
+    assert_eq!(kth(0).as_deref(), Some("i"));
+    assert_eq!(kth(1).as_deref(), Some("ip"));
+    assert_eq!(kth(52).as_deref(), Some("ssissippi"));
+    assert_eq!(kth(53), None);
+    let occurrences = automaton.occurrences();
+    let node_i = |sub: &str| {
+        let mut cur_i = HashMapSuffixAutomaton::<char>::ROOT_NODE_I;
+        for c in sub.chars() {
+            cur_i = automaton.node(cur_i).nxt.get(&c).unwrap();
+        }
+        cur_i
+    };
+    assert_eq!(occurrences[node_i("ssi")], 2);
+    assert_eq!(occurrences[node_i("i")], 4);
+    assert_eq!(occurrences[node_i("pp")], 1);
+}
+
+#[test]
+fn generalized() {
+    let alphabet = b'a'..b'c';
+    let strings = generate_all_strings(alphabet.clone(), 4);
+    for (i, s) in strings.iter().enumerate().skip(1) {
+        for t in strings.iter().skip(i).step_by(2) {
+            check_generalized(&[s.clone(), t.clone()], alphabet.clone());
+        }
+        let u = &strings[strings.len() - i];
+        check_generalized(&[s.clone(), u.clone(), s.clone()], alphabet.clone());
+    }
+}
+
+#[test]
+fn generalized_hash_map() {
+    let mut automaton = HashMapSuffixAutomaton::new();
+    for s in ["banana", "ananas", "bandana"] {
+        automaton.add_string(s.chars().into_vec().into_iter());
+    }
+    assert_eq!(automaton.longest_common_len(3), 3);
+    assert_eq!(automaton.longest_common_len(2), 5);
+    assert_eq!(automaton.longest_common_len(4), 0);
+    // "a", "n", "an", "na", "ana"
+    assert_eq!(automaton.common_substrings(3), 5);
+    assert!(automaton.contains("andana".chars()));
+    assert!(!automaton.contains("nab".chars()));
+}
+
+fn check_generalized(strings: &[Str], alphabet: Range<u8>) {
+    let mut automaton = LowerCaseSuffixAutomaton::new();
+    for s in strings {
+        automaton.add_string(s.iter().cloned());
+    }
+    let all_substrings = strings.iter().map(generate_all_substrings).into_vec();
+    let max_len = strings.iter().map(|s| s.len()).max().unwrap();
+    let occurrences = automaton.occurrences();
+    let mut common = vec![0; strings.len() + 1];
+    let mut longest_common = vec![0; strings.len() + 1];
+    for sub in generate_all_strings(alphabet, max_len).into_iter().skip(1) {
+        let present = all_substrings
+            .iter()
+            .filter(|set| set.contains(&sub))
+            .count();
+        assert_eq!(
+            automaton.contains(sub.iter().cloned()),
+            present > 0,
+            "'{sub}' in {strings:?}"
+        );
+        if present == 0 {
+            continue;
+        }
+        let node_i = find_node_i(&automaton, &sub);
+        assert_eq!(
+            automaton.node(node_i).strings,
+            present,
+            "'{sub}' in {strings:?}"
+        );
+        let expected = strings
+            .iter()
+            .map(|s| s.windows(sub.len()).filter(|w| *w == &sub[..]).count())
+            .sum::<usize>();
+        assert_eq!(occurrences[node_i], expected, "'{sub}' in {strings:?}");
+        for k in 1..=present {
+            common[k] += 1;
+            longest_common[k] = std::cmp::max(longest_common[k], sub.len());
+        }
+    }
+    assert_eq!(automaton.distinct_substrings(), common[1]);
+    for k in 1..=strings.len() {
+        assert_eq!(
+            automaton.common_substrings(k),
+            common[k],
+            "{k}, {strings:?}"
+        );
+        assert_eq!(
+            automaton.longest_common_len(k),
+            longest_common[k],
+            "{k}, {strings:?}"
+        );
+    }
+}
+
+fn find_node_i(automaton: &LowerCaseSuffixAutomaton, sub: &Str) -> usize {
+    let mut cur_i = LowerCaseSuffixAutomaton::ROOT_NODE_I;
+    for c in sub.iter() {
+        cur_i = automaton.node(cur_i).nxt.get(c).unwrap();
+    }
+    cur_i
+}
+
+fn check_analytics(s: &Str) {
+    let mut automaton = LowerCaseSuffixAutomaton::new();
+    automaton.add_all(s.iter().cloned());
+    let mut substrings = generate_all_substrings(s)
+        .into_iter()
+        .filter(|sub| !sub.is_empty())
+        .into_vec();
+    substrings.sort();
+    assert_eq!(automaton.distinct_substrings(), substrings.len(), "'{s}'");
+    assert_eq!(
+        automaton.distinct_substrings_len(),
+        substrings.iter().map(|sub| sub.len()).sum::<usize>(),
+        "'{s}'"
+    );
+
+    let order = automaton.topological_order();
+    for w in order.windows(2) {
+        assert!(automaton.node(w[0]).len <= automaton.node(w[1]).len);
+    }
+
+    let occurrences = automaton.occurrences();
+    for sub in substrings.iter() {
+        let cur_i = find_node_i(&automaton, sub);
+        let expected = s.windows(sub.len()).filter(|w| *w == &sub[..]).count();
+        assert_eq!(occurrences[cur_i], expected, "'{sub}' in '{s}'");
+    }
+
+    let counts = automaton.substring_counts();
+    for (k, sub) in substrings.iter().enumerate() {
+        let actual = automaton.kth_substring(&counts, k).map(Str);
+        assert_eq!(actual.as_ref(), Some(sub), "k: {k}, s: '{s}'");
+    }
+    assert_eq!(automaton.kth_substring(&counts, substrings.len()), None);
+}
+
 fn check_contains(s: Str, alphabet: Range<u8>) {
     let mut automaton = LowerCaseSuffixAutomaton::new();
     automaton.add_all(s.iter().cloned());
@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 use std::iter::Sum;
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use std::str::FromStr;
 
 use super::modulo_ops::{modulo_combinations, modulo_inv, modulo_pow, Factorials};
@@ -116,6 +116,14 @@ where
     }
 }
 
+impl<const MOD: u64> Neg for Modulo<MOD> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::ZERO - self
+    }
+}
+
 impl<const MOD: u64> Mul for Modulo<MOD> {
     type Output = Self;
 
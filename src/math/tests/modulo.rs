@@ -29,6 +29,12 @@ fn modulo_sub() {
     check_modulo(v, 5);
 }
 
+#[test]
+fn modulo_neg() {
+    check_modulo(-Mod::new(3), 8);
+    check_modulo(-Mod::ZERO, 0);
+}
+
 #[test]
 fn modulo_mul() {
     let mut v = Mod::new(5);
@@ -0,0 +1,29 @@
+pub enum CheckMode {
+    Print,
+    /// Every test case in the input is followed by the expected output line
+    Expected {
+        any_order: bool,
+    },
+}
+
+/// # Examples
+/// ```
+/// check_mode!(print);
+/// check_mode!(expected);
+/// check_mode!(expected, any_order);
+/// ```
+#[macro_export]
+macro_rules! check_mode {
+    (print) => {
+        pub const CHECK_MODE: $crate::plat::leetcode::config::CheckMode =
+            $crate::plat::leetcode::config::CheckMode::Print;
+    };
+    (expected) => {
+        pub const CHECK_MODE: $crate::plat::leetcode::config::CheckMode =
+            $crate::plat::leetcode::config::CheckMode::Expected { any_order: false };
+    };
+    (expected, any_order) => {
+        pub const CHECK_MODE: $crate::plat::leetcode::config::CheckMode =
+            $crate::plat::leetcode::config::CheckMode::Expected { any_order: true };
+    };
+}
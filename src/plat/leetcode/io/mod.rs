@@ -1,10 +1,11 @@
-use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
 use crate::utils::io::{InputSource, OutputTarget};
 
-use self::parser::LeetcodeValueNode;
+pub use self::parser::LeetcodeValueNode;
 
 use super::defs::TreeNode;
+use super::tree::{tree_from_level_order, tree_to_level_order};
 
 mod parser;
 
@@ -12,37 +13,151 @@ pub struct InputReader {
     source: InputSource,
 }
 
+/// # Examples
+/// ```
+/// with_input! { r => two_sum(nums, target) }
+/// with_input! { r => two_sum, 2 }
+/// ```
 #[macro_export]
 macro_rules! with_input {
+    ($r:ident => $func:ident($($arg:ident),*)) => {{
+        $( let $arg = $r.read(); )*
+        $crate::plat::leetcode::solution::Solution::$func($($arg),*)
+    }};
     ($r:ident => $func:ident, 1) => {
-        crate::plat::leetcode::solution::Solution::$func($r.read())
+        $crate::with_input!($r => $func(a1))
     };
     ($r:ident => $func:ident, 2) => {
-        crate::plat::leetcode::solution::Solution::$func($r.read(), $r.read())
+        $crate::with_input!($r => $func(a1, a2))
     };
     ($r:ident => $func:ident, 3) => {
-        crate::plat::leetcode::solution::Solution::$func($r.read(), $r.read(), $r.read())
+        $crate::with_input!($r => $func(a1, a2, a3))
     };
 }
 
+/// Runs a design problem: the first input line lists operations, the second one their arguments.
+/// Argument names are only used to count the arguments, their types come from the method signatures.
+///
+/// # Examples
+/// ```
+/// with_design_input! { r => LRUCache::new(capacity);
+///     get(key);
+///     put(key, value);
+/// }
+/// /*
+/// ["LRUCache","put","get"]
+/// [[2],[1,1],[1]]
+/// =>
+/// [null,null,1]
+/// */
+/// ```
+#[macro_export]
+macro_rules! with_design_input {
+    ($r:ident => $class:ident::new($($ctor_arg:ident),*); $($method:ident($($arg:ident),*));* $(;)?) => {{
+        let mut ops = $r.read_design_operations().into_iter();
+        #[allow(unused_mut)]
+        let mut ctor = ops.next().expect("no constructor call");
+        assert_eq!(ctor.name, stringify!($class), "first operation should be the constructor");
+        $( let $ctor_arg = ctor.arg(); )*
+        ctor.assert_consumed();
+        #[allow(unused_mut)]
+        let mut obj = $class::new($($ctor_arg),*);
+        let mut res = vec![$crate::plat::leetcode::io::ToLeetcodeValueNode::to_leetcode_value_node(&())];
+        for mut op in ops {
+            let ret = $(
+                if op.is(stringify!($method)) {
+                    $( let $arg = op.arg(); )*
+                    op.assert_consumed();
+                    $crate::plat::leetcode::io::ToLeetcodeValueNode::to_leetcode_value_node(
+                        &obj.$method($($arg),*)
+                    )
+                } else
+            )* {
+                panic!("unknown operation {:?}", op.name)
+            };
+            res.push(ret);
+        }
+        res
+    }};
+}
+
 pub struct OutputPrinter {
     target: OutputTarget,
 }
 
+pub struct DesignOperation {
+    pub name: String,
+    args: std::vec::IntoIter<LeetcodeValueNode>,
+}
+
 impl InputReader {
+    pub fn new(source: InputSource) -> Self {
+        Self { source }
+    }
+
     pub fn read<T: FromLeetcodeValueNode>(&mut self) -> T {
+        T::from_leetcode_input_node(&self.read_node())
+    }
+
+    pub fn read_design_operations(&mut self) -> Vec<DesignOperation> {
+        let names: Vec<String> = self.read();
+        let args: Vec<Vec<LeetcodeValueNode>> = self.read();
+        assert_eq!(names.len(), args.len(), "operations and arguments mismatch");
+        names
+            .into_iter()
+            .zip(args)
+            .map(|(name, args)| DesignOperation {
+                name,
+                args: args.into_iter(),
+            })
+            .collect()
+    }
+
+    fn read_node(&mut self) -> LeetcodeValueNode {
         let mut line = String::new();
-        self.source.reader().read_line(&mut line).unwrap();
-        let node = LeetcodeValueNode::parse_node(&line);
+        while line.trim().is_empty() {
+            line.clear();
+            let read = self.source.reader().read_line(&mut line).unwrap();
+            assert!(read > 0, "unexpected end of input");
+        }
+        LeetcodeValueNode::parse_node(&line)
+    }
+}
+
+impl DesignOperation {
+    /// LeetCode lists operations in camelCase while Rust methods are snake_case
+    pub fn is(&self, method: &str) -> bool {
+        let mut snake = String::new();
+        for ch in self.name.chars() {
+            if ch.is_ascii_uppercase() {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        }
+        snake == method
+    }
+
+    pub fn arg<T: FromLeetcodeValueNode>(&mut self) -> T {
+        let node = self
+            .args
+            .next()
+            .unwrap_or_else(|| panic!("not enough arguments for {}", self.name));
         T::from_leetcode_input_node(&node)
     }
+
+    pub fn assert_consumed(&self) {
+        assert!(
+            self.args.len() == 0,
+            "too many arguments for {}: {:?}",
+            self.name,
+            self.args.as_slice()
+        );
+    }
 }
 
 impl Default for InputReader {
     fn default() -> Self {
-        Self {
-            source: InputSource::from_env(),
-        }
+        Self::new(InputSource::from_env())
     }
 }
 
@@ -88,7 +203,7 @@ macro_rules! impl_from_leetcode_int {
     };
 }
 
-impl_from_leetcode_int!(i32, i64);
+impl_from_leetcode_int!(i32, i64, u32);
 
 impl<T: FromLeetcodeValueNode> FromLeetcodeValueNode for Option<T> {
     fn from_leetcode_input_node(node: &LeetcodeValueNode) -> Self {
@@ -102,46 +217,22 @@ impl<T: FromLeetcodeValueNode> FromLeetcodeValueNode for Option<T> {
 impl FromLeetcodeValueNode for Option<Rc<RefCell<TreeNode>>> {
     fn from_leetcode_input_node(node: &LeetcodeValueNode) -> Self {
         match node {
-            LeetcodeValueNode::Array(a) => convert_to_tree_node(a),
+            LeetcodeValueNode::Array(a) => {
+                tree_from_level_order(&a.iter().map(tree_node_val).collect::<Vec<_>>())
+            }
             other => panic!("{:?} cannot be parsed to TreeNode, expected array", other),
         }
     }
 }
 
-fn create_tree_node(node: Option<&LeetcodeValueNode>) -> Option<Rc<RefCell<TreeNode>>> {
+fn tree_node_val(node: &LeetcodeValueNode) -> Option<i32> {
     match node {
-        None | Some(LeetcodeValueNode::Null) => None,
-        Some(LeetcodeValueNode::Int(v)) => Some(Rc::new(RefCell::new(TreeNode::new(*v as i32)))),
+        LeetcodeValueNode::Null => None,
+        LeetcodeValueNode::Int(v) => Some(*v as i32),
         other => panic!("expected int or null, got {:?}", other),
     }
 }
 
-fn convert_to_tree_node(a: &[LeetcodeValueNode]) -> Option<Rc<RefCell<TreeNode>>> {
-    let mut q = VecDeque::<Rc<RefCell<TreeNode>>>::new();
-    let root = create_tree_node(a.get(0));
-    if let Some(ref rc) = root {
-        q.push_back(rc.clone());
-    } else {
-        return None;
-    }
-    let mut i = 1;
-    while let Some(rc) = q.pop_front() {
-        let mut node = rc.borrow_mut();
-        node.left = create_tree_node(a.get(i));
-        node.right = create_tree_node(a.get(i + 1));
-        i += 2;
-        for child in [&node.left, &node.right].iter() {
-            if let Some(rc) = child {
-                q.push_back(rc.clone());
-            }
-        }
-    }
-    if i < a.len() {
-        panic!("{:?} contains redundant elements, consumed {}", a, i + 1);
-    }
-    root
-}
-
 impl FromLeetcodeValueNode for String {
     fn from_leetcode_input_node(node: &LeetcodeValueNode) -> Self {
         if let LeetcodeValueNode::Str(s) = node {
@@ -152,6 +243,15 @@ impl FromLeetcodeValueNode for String {
     }
 }
 
+impl FromLeetcodeValueNode for char {
+    fn from_leetcode_input_node(node: &LeetcodeValueNode) -> Self {
+        match node {
+            LeetcodeValueNode::Str(s) if s.chars().count() == 1 => s.chars().next().unwrap(),
+            other => panic!("{:?} is not char", other),
+        }
+    }
+}
+
 impl FromLeetcodeValueNode for bool {
     fn from_leetcode_input_node(node: &LeetcodeValueNode) -> Self {
         if let LeetcodeValueNode::Bool(v) = node {
@@ -162,6 +262,12 @@ impl FromLeetcodeValueNode for bool {
     }
 }
 
+impl FromLeetcodeValueNode for LeetcodeValueNode {
+    fn from_leetcode_input_node(node: &LeetcodeValueNode) -> Self {
+        node.clone()
+    }
+}
+
 impl<T: FromLeetcodeValueNode> FromLeetcodeValueNode for Vec<T> {
     fn from_leetcode_input_node(node: &LeetcodeValueNode) -> Self {
         if let LeetcodeValueNode::Array(a) = node {
@@ -184,7 +290,7 @@ macro_rules! impl_to_leetcode_int {
     };
 }
 
-impl_to_leetcode_int!(i32, i64);
+impl_to_leetcode_int!(i32, i64, u32);
 
 impl<T: ToLeetcodeValueNode> ToLeetcodeValueNode for Option<T> {
     fn to_leetcode_value_node(&self) -> LeetcodeValueNode {
@@ -201,12 +307,36 @@ impl ToLeetcodeValueNode for String {
     }
 }
 
+impl ToLeetcodeValueNode for char {
+    fn to_leetcode_value_node(&self) -> LeetcodeValueNode {
+        LeetcodeValueNode::Str(self.to_string())
+    }
+}
+
 impl ToLeetcodeValueNode for bool {
     fn to_leetcode_value_node(&self) -> LeetcodeValueNode {
         LeetcodeValueNode::Bool(*self)
     }
 }
 
+impl ToLeetcodeValueNode for () {
+    fn to_leetcode_value_node(&self) -> LeetcodeValueNode {
+        LeetcodeValueNode::Null
+    }
+}
+
+impl ToLeetcodeValueNode for LeetcodeValueNode {
+    fn to_leetcode_value_node(&self) -> LeetcodeValueNode {
+        self.clone()
+    }
+}
+
+impl ToLeetcodeValueNode for Option<Rc<RefCell<TreeNode>>> {
+    fn to_leetcode_value_node(&self) -> LeetcodeValueNode {
+        tree_to_level_order(self).to_leetcode_value_node()
+    }
+}
+
 impl<T: ToLeetcodeValueNode> ToLeetcodeValueNode for Vec<T> {
     fn to_leetcode_value_node(&self) -> LeetcodeValueNode {
         LeetcodeValueNode::Array(self.iter().map(|el| el.to_leetcode_value_node()).collect())
@@ -1,4 +1,6 @@
-#[derive(Debug)]
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LeetcodeValueNode {
     Null,
     Int(i64),
@@ -19,14 +21,15 @@ impl LeetcodeValueNode {
     }
 }
 
-impl ToString for LeetcodeValueNode {
-    fn to_string(&self) -> String {
+impl Display for LeetcodeValueNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            LeetcodeValueNode::Null => "null".to_string(),
-            LeetcodeValueNode::Int(v) => v.to_string(),
-            LeetcodeValueNode::Str(s) => s.to_string(),
-            LeetcodeValueNode::Bool(v) => v.to_string(),
-            LeetcodeValueNode::Array(a) => format!(
+            LeetcodeValueNode::Null => write!(f, "null"),
+            LeetcodeValueNode::Int(v) => write!(f, "{v}"),
+            LeetcodeValueNode::Str(s) => write!(f, "{s}"),
+            LeetcodeValueNode::Bool(v) => write!(f, "{v}"),
+            LeetcodeValueNode::Array(a) => write!(
+                f,
                 "[{}]",
                 a.iter()
                     .map(|el| el.to_string())
@@ -41,7 +44,7 @@ fn parse_next(s: &mut &str) -> LeetcodeValueNode {
     trim_start(s);
     match s.chars().next().unwrap() {
         'n' => parse_next_null(s),
-        '0'..='9' => parse_next_int(s),
+        '-' | '0'..='9' => parse_next_int(s),
         't' | 'f' => parse_next_bool(s),
         '"' => parse_next_str(s),
         '[' => parse_next_array(s),
@@ -57,6 +60,7 @@ fn parse_next_null(s: &mut &str) -> LeetcodeValueNode {
 }
 
 fn parse_next_int(s: &mut &str) -> LeetcodeValueNode {
+    let sign = if consume_start(s, "-") { -1 } else { 1 };
     let mut v = 0;
     while let Some(ch) = s.chars().next() {
         if let Some(d) = ch.to_digit(10) {
@@ -66,7 +70,7 @@ fn parse_next_int(s: &mut &str) -> LeetcodeValueNode {
             break;
         }
     }
-    LeetcodeValueNode::Int(v)
+    LeetcodeValueNode::Int(sign * v)
 }
 
 fn parse_next_str(s: &mut &str) -> LeetcodeValueNode {
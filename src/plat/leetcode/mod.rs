@@ -1,16 +1,61 @@
-use self::io::{InputReader, OutputPrinter};
+use self::config::CheckMode;
+use self::io::{InputReader, LeetcodeValueNode, OutputPrinter, ToLeetcodeValueNode};
 
 pub mod solution;
 
+pub mod config;
 pub mod defs;
 pub mod io;
+pub mod tree;
+
+#[cfg(test)]
+mod tests;
 
 pub fn run() {
     let mut reader = InputReader::default();
     let mut printer = OutputPrinter::default();
-    for _ in 0..solution::TEST_COUNT {
-        let res = solution::exec(&mut reader);
-        printer.print(&res);
-        printer.print(&String::from("\n"));
+    for tc in 1..=solution::TEST_COUNT {
+        let res = solution::exec(&mut reader).to_leetcode_value_node();
+        match solution::CHECK_MODE {
+            CheckMode::Print => {
+                printer.print(&res);
+                printer.print(&String::from("\n"));
+            }
+            CheckMode::Expected { any_order } => {
+                printer.print(&check_expected(tc, &res, &mut reader, any_order));
+            }
+        }
+    }
+}
+
+/// Reads the expected output of the test case and compares `res` with it
+fn check_expected(
+    tc: usize,
+    res: &LeetcodeValueNode,
+    reader: &mut InputReader,
+    any_order: bool,
+) -> String {
+    let expected: LeetcodeValueNode = reader.read();
+    if results_match(res, &expected, any_order) {
+        format!("Case #{tc}: OK\n")
+    } else {
+        format!("Case #{tc}: FAILED\n  expected: {expected}\n  actual:   {res}\n")
+    }
+}
+
+fn results_match(
+    actual: &LeetcodeValueNode,
+    expected: &LeetcodeValueNode,
+    any_order: bool,
+) -> bool {
+    match (actual, expected) {
+        (LeetcodeValueNode::Array(a), LeetcodeValueNode::Array(b)) if any_order => {
+            let mut a = a.clone();
+            let mut b = b.clone();
+            a.sort();
+            b.sort();
+            a == b
+        }
+        _ => actual == expected,
     }
 }
@@ -4,11 +4,12 @@ impl Solution {
     }
 }
 
-use crate::{plat::leetcode::io::{InputReader, ToLeetcodeValueNode}, with_input};
+use crate::{check_mode, plat::leetcode::io::{InputReader, ToLeetcodeValueNode}, with_input};
 pub const TEST_COUNT: usize = 1;
+check_mode!(print);
 
 pub fn exec(r: &mut InputReader) -> impl ToLeetcodeValueNode {
-    with_input!{ r => echo, 1 }
+    with_input!{ r => echo(n) }
 }
 
 struct Solution;
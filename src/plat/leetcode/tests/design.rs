@@ -0,0 +1,108 @@
+use std::io::Write;
+
+use crate::plat::leetcode::io::{InputReader, LeetcodeValueNode};
+use crate::utils::{io::InputSource, sync::Pipe};
+use crate::with_design_input;
+
+struct NumberBuilder {
+    prefix: String,
+    value: i32,
+}
+
+impl NumberBuilder {
+    fn new(prefix: String, base: i32) -> Self {
+        Self {
+            prefix,
+            value: base,
+        }
+    }
+
+    fn add_digits(&mut self, tens: i32, ones: i32) {
+        self.value += 10 * tens + ones;
+    }
+
+    fn get_value(&self) -> i32 {
+        self.value
+    }
+
+    fn describe(&self) -> String {
+        format!("{}{}", self.prefix, self.value)
+    }
+}
+
+#[test]
+fn design_run() {
+    let mut r = reader(
+        r#"["NumberBuilder","addDigits","getValue","addDigits","describe"]
+[["n",100],[1,2],[],[3,0],[]]
+"#,
+    );
+    let res = run(&mut r);
+    let expected = LeetcodeValueNode::parse_node(r#"[null,null,112,null,"n142"]"#);
+    assert_eq!(LeetcodeValueNode::Array(res), expected);
+}
+
+#[test]
+fn design_operation_names() {
+    let mut r = reader(
+        r#"["NumberBuilder","addDigits","getValue"]
+[["n",0],[1,2],[]]
+"#,
+    );
+    let ops = r.read_design_operations();
+    assert!(ops[1].is("add_digits"));
+    assert!(!ops[1].is("adddigits"));
+    assert!(!ops[1].is("addDigits"));
+    assert!(ops[2].is("get_value"));
+}
+
+#[test]
+fn design_args_in_order() {
+    let mut r = reader(
+        r#"["NumberBuilder","addDigits"]
+[["n",7],[1,2]]
+"#,
+    );
+    let mut ops = r.read_design_operations();
+    assert_eq!(ops[0].arg::<String>(), "n");
+    assert_eq!(ops[0].arg::<i32>(), 7);
+    ops[0].assert_consumed();
+    assert_eq!((ops[1].arg::<i32>(), ops[1].arg::<i32>()), (1, 2));
+    ops[1].assert_consumed();
+}
+
+#[test]
+#[should_panic(expected = "too many arguments for addDigits")]
+fn design_unread_arg() {
+    let mut r = reader(
+        r#"["NumberBuilder","addDigits"]
+[["n",0],[1,2,3]]
+"#,
+    );
+    run(&mut r);
+}
+
+#[test]
+#[should_panic(expected = "not enough arguments for NumberBuilder")]
+fn design_missing_arg() {
+    let mut r = reader(
+        r#"["NumberBuilder"]
+[["n"]]
+"#,
+    );
+    run(&mut r);
+}
+
+fn run(r: &mut InputReader) -> Vec<LeetcodeValueNode> {
+    with_design_input! { r => NumberBuilder::new(prefix, base);
+        add_digits(tens, ones);
+        get_value();
+        describe();
+    }
+}
+
+fn reader(input: &str) -> InputReader {
+    let mut pipe = Pipe::new();
+    pipe.write_all(input.as_bytes()).unwrap();
+    InputReader::new(InputSource::from_pipe(pipe))
+}
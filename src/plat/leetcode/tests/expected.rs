@@ -0,0 +1,50 @@
+use std::io::Write;
+
+use crate::plat::leetcode::check_expected;
+use crate::plat::leetcode::io::{InputReader, LeetcodeValueNode};
+use crate::utils::{io::InputSource, sync::Pipe};
+
+#[test]
+fn expected_match() {
+    let mut r = reader("[[1,2],[3]]\n\n[2,1]\n");
+    let res = LeetcodeValueNode::parse_node("[[1,2],[3]]");
+    assert_eq!(check_expected(1, &res, &mut r, false), "Case #1: OK\n");
+    let res = LeetcodeValueNode::parse_node("[1,2]");
+    assert_eq!(
+        check_expected(2, &res, &mut r, false),
+        "Case #2: FAILED\n  expected: [2,1]\n  actual:   [1,2]\n"
+    );
+}
+
+#[test]
+fn expected_mismatch() {
+    let mut r = reader("[1,2,3]\n");
+    let res = LeetcodeValueNode::parse_node("[1,2,4]");
+    assert_eq!(
+        check_expected(3, &res, &mut r, true),
+        "Case #3: FAILED\n  expected: [1,2,3]\n  actual:   [1,2,4]\n"
+    );
+}
+
+#[test]
+fn expected_any_order() {
+    let res = LeetcodeValueNode::parse_node("[[3,4],[1,2],[1,2]]");
+    let expected = "[[1,2],[3,4],[1,2]]\n";
+    assert_eq!(
+        check_expected(1, &res, &mut reader(expected), true),
+        "Case #1: OK\n"
+    );
+    assert!(check_expected(1, &res, &mut reader(expected), false).contains("FAILED"));
+    // Only the outer array is reordered
+    let expected = "[[2,1],[3,4],[1,2]]\n";
+    assert!(check_expected(1, &res, &mut reader(expected), true).contains("FAILED"));
+    // Same elements with different multiplicities
+    let expected = "[[1,2],[3,4],[3,4]]\n";
+    assert!(check_expected(1, &res, &mut reader(expected), true).contains("FAILED"));
+}
+
+fn reader(input: &str) -> InputReader {
+    let mut pipe = Pipe::new();
+    pipe.write_all(input.as_bytes()).unwrap();
+    InputReader::new(InputSource::from_pipe(pipe))
+}
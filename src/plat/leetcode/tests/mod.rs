@@ -0,0 +1,3 @@
+mod design;
+mod expected;
+mod tree;
@@ -0,0 +1,39 @@
+use crate::plat::leetcode::tree::{pretty_tree, tree_from_level_order, tree_to_level_order};
+use crate::tree;
+
+#[test]
+fn tree_macro() {
+    let root = tree![1, 2, null, -3];
+    let node = root.as_ref().unwrap().borrow();
+    assert_eq!(node.val, 1);
+    assert!(node.right.is_none());
+    let left = node.left.as_ref().unwrap().borrow();
+    assert_eq!(left.val, 2);
+    assert_eq!(left.left.as_ref().unwrap().borrow().val, -3);
+    assert!(tree![].is_none());
+}
+
+#[test]
+fn tree_level_order_roundtrip() {
+    for a in [
+        vec![],
+        vec![Some(1)],
+        vec![Some(3), Some(9), Some(20), None, None, Some(15), Some(7)],
+        vec![Some(1), None, Some(2), None, Some(3), Some(4)],
+    ] {
+        let root = tree_from_level_order(&a);
+        assert_eq!(tree_to_level_order(&root), a);
+    }
+}
+
+#[test]
+fn tree_pretty() {
+    let actual = pretty_tree(&tree![1, 2, 3, null, 4, 5]);
+    let expected = "    /-- 3
+    |   \\-- 5
+1
+    |   /-- 4
+    \\-- 2
+";
+    assert_eq!(actual, expected, "\n{actual}");
+}
@@ -0,0 +1,125 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use super::defs::TreeNode;
+
+pub type Tree = Option<Rc<RefCell<TreeNode>>>;
+
+/// Builds a tree from LeetCode level order notation
+///
+/// # Examples
+/// ```
+/// let root = tree![1, 2, null, 3];
+/// ```
+#[macro_export]
+macro_rules! tree {
+    (@acc [$($acc:expr,)*]) => {
+        $crate::plat::leetcode::tree::tree_from_level_order(&[$($acc),*])
+    };
+    (@acc [$($acc:expr,)*] null $(, $($rest:tt)*)?) => {
+        $crate::tree!(@acc [$($acc,)* None,] $($($rest)*)?)
+    };
+    (@acc [$($acc:expr,)*] $val:expr $(, $($rest:tt)*)?) => {
+        $crate::tree!(@acc [$($acc,)* Some($val),] $($($rest)*)?)
+    };
+    ($($vals:tt)*) => {
+        $crate::tree!(@acc [] $($vals)*)
+    };
+}
+
+pub fn tree_from_level_order(a: &[Option<i32>]) -> Tree {
+    let create_node = |i: usize| {
+        a.get(i)
+            .cloned()
+            .flatten()
+            .map(|val| Rc::new(RefCell::new(TreeNode::new(val))))
+    };
+    let mut q = VecDeque::<Rc<RefCell<TreeNode>>>::new();
+    let root = create_node(0);
+    if let Some(ref rc) = root {
+        q.push_back(rc.clone());
+    } else {
+        return None;
+    }
+    let mut i = 1;
+    while let Some(rc) = q.pop_front() {
+        let mut node = rc.borrow_mut();
+        node.left = create_node(i);
+        node.right = create_node(i + 1);
+        i += 2;
+        for rc in [&node.left, &node.right].into_iter().flatten() {
+            q.push_back(rc.clone());
+        }
+    }
+    if i < a.len() {
+        panic!("{:?} contains redundant elements, consumed {}", a, i + 1);
+    }
+    root
+}
+
+pub fn tree_to_level_order(root: &Tree) -> Vec<Option<i32>> {
+    let mut ret = Vec::new();
+    let mut q = VecDeque::new();
+    q.push_back(root.clone());
+    while let Some(node) = q.pop_front() {
+        if let Some(rc) = node {
+            let node = rc.borrow();
+            ret.push(Some(node.val));
+            q.push_back(node.left.clone());
+            q.push_back(node.right.clone());
+        } else {
+            ret.push(None);
+        }
+    }
+    while ret.last() == Some(&None) {
+        ret.pop();
+    }
+    ret
+}
+
+/// Draws the tree sideways: right subtree above the node, left subtree below
+///
+/// # Examples
+/// ```
+/// pretty_tree(&tree![1, 2, 3, null, 4]);
+/// /*
+///     /-- 3
+/// 1
+///     |   /-- 4
+///     \-- 2
+/// */
+/// ```
+pub fn pretty_tree(root: &Tree) -> String {
+    let mut ret = String::new();
+    draw_subtree(root, "", Edge::Root, &mut ret);
+    ret
+}
+
+enum Edge {
+    Root,
+    Right,
+    Left,
+}
+
+fn draw_subtree(node: &Tree, prefix: &str, edge: Edge, out: &mut String) {
+    if let Some(rc) = node {
+        let node = rc.borrow();
+        let (connector, right_prefix, left_prefix) = match edge {
+            Edge::Root => ("", "    ", "    "),
+            Edge::Right => ("/-- ", "    ", "|   "),
+            Edge::Left => ("\\-- ", "|   ", "    "),
+        };
+        draw_subtree(
+            &node.right,
+            &(prefix.to_owned() + right_prefix),
+            Edge::Right,
+            out,
+        );
+        *out += &format!("{prefix}{connector}{}\n", node.val);
+        draw_subtree(
+            &node.left,
+            &(prefix.to_owned() + left_prefix),
+            Edge::Left,
+            out,
+        );
+    }
+}